@@ -0,0 +1,58 @@
+//! Runs the same statements against interpreters with a growing number of
+//! registered commands and cvars and prints the time per statement, which
+//! stays flat when looking up a command takes constant time. Bigger tables
+//! still cost somewhat more once they stop fitting in the CPU caches.
+//!
+//! Run with `cargo bench --bench dispatch`
+
+use std::hint::black_box;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use haybcmd::cvar::FCVAR_NONE;
+use haybcmd::output::BufferSink;
+use haybcmd::Interpreter;
+
+const STATEMENTS: usize = 10_000;
+
+fn interpreter(commands: usize) -> Interpreter {
+    let mut interpreter = Interpreter::with_output(BufferSink::new());
+
+    for i in 0..commands {
+        interpreter.register(&format!("cmd{}", i), |_interpreter, args| {
+            black_box(args.len());
+            Ok(())
+        });
+        interpreter.commands_handler.register_cvar(&format!("cvar{}", i), i as i64, "", FCVAR_NONE);
+    }
+
+    interpreter
+}
+
+fn main() {
+    let mut previous: Option<f64> = None;
+
+    for commands in [1_000, 10_000, 100_000] {
+        let mut interpreter = interpreter(commands);
+
+        // spreads the calls over the whole table, including the last registered ones
+        let input: Vec<String> = (0..STATEMENTS)
+            .map(|i| format!("cmd{} a b; cvar{} 1", i * 7919 % commands, i * 104729 % commands))
+            .collect();
+        let script = Rc::new(interpreter.parse(&input.join("; ")).unwrap());
+
+        let mut best = Duration::MAX;
+        for _ in 0..5 {
+            let start = Instant::now();
+            interpreter.run(black_box(&script)).unwrap();
+            best = best.min(start.elapsed());
+        }
+
+        let statements = script.statements.len();
+        let per_statement = best.as_nanos() as f64 / statements as f64;
+        let growth = previous.map_or(String::new(), |previous| format!(" ({:.2}x the previous ns/statement)", per_statement / previous));
+        println!("{:>7} commands and cvars, {:>6} statements: {:>10.3?} {:.1} ns/statement{}", commands, statements, best, per_statement, growth);
+
+        previous = Some(per_statement);
+    }
+}
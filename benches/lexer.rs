@@ -0,0 +1,53 @@
+//! Tokenizes generated config files of growing size and prints the time
+//! per byte, which stays flat when lexing is linear.
+//!
+//! Run with `cargo bench --bench lexer`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use haybcmd::command::CommandsHandler;
+use haybcmd::lexer::Lexer;
+use haybcmd::token::TokenType;
+
+fn config(lines: usize) -> String {
+    let mut out = String::new();
+    for i in 0..lines {
+        out += &format!("alias \"combo{}\" \"echo héllo wörld; sensitivity {}\"; // comment ✓\n", i, i % 10);
+    }
+    out
+}
+
+fn tokenize(input: &str, commands_handler: &CommandsHandler) -> usize {
+    let mut lexer = Lexer::new(input.to_string());
+    let mut count = 0;
+
+    while lexer.next_token(commands_handler).unwrap().token_type() != &TokenType::EOF {
+        count += 1;
+    }
+
+    count
+}
+
+fn main() {
+    let commands_handler = CommandsHandler::new();
+    let mut previous: Option<f64> = None;
+
+    for lines in [1_000, 10_000, 100_000] {
+        let input = config(lines);
+
+        let mut best = Duration::MAX;
+        let mut tokens = 0;
+        for _ in 0..5 {
+            let start = Instant::now();
+            tokens = black_box(tokenize(black_box(&input), &commands_handler));
+            best = best.min(start.elapsed());
+        }
+
+        let per_byte = best.as_nanos() as f64 / input.len() as f64;
+        let growth = previous.map_or(String::new(), |previous| format!(" ({:.2}x the previous ns/byte)", per_byte / previous));
+        println!("{:>7} lines, {:>9} bytes, {:>8} tokens: {:>10.3?} {:.2} ns/byte{}", lines, input.len(), tokens, best, per_byte, growth);
+
+        previous = Some(per_byte);
+    }
+}
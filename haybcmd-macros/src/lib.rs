@@ -0,0 +1,231 @@
+//! `#[haybcmd::command]`, which turns a function into a haybcmd command.
+//!
+//! ```ignore
+//! #[haybcmd::command(name = "give", usage = "gives an item to the player")]
+//! fn give(interpreter: &mut haybcmd::Interpreter, item: String, count: u32) {
+//!     interpreter.output.println(&format!("{} x{}", item, count));
+//! }
+//!
+//! interpreter.register_definition(give);
+//! ```
+//!
+//! The first parameter receives the interpreter and the others become the
+//! command parameters: `String`, `bool`, integers and floats, `Option<T>` for
+//! optional ones and a final `Vec<T>` taking every remaining argument. Only
+//! `Option`s and the `Vec` can follow an `Option`. The function may return
+//! nothing or `Result<(), haybcmd::ExecError>`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprLit, FnArg, GenericArgument, ItemFn, Lit, MetaNameValue, PathArguments, ReturnType, Token, Type};
+
+#[proc_macro_attribute]
+pub fn command(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let function = syn::parse_macro_input!(item as ItemFn);
+
+    match expand(attr.into(), function) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// `name = "..."` and `usage = "..."`
+struct Attributes {
+    name: Option<String>,
+    usage: String,
+}
+
+fn parse_attributes(attr: TokenStream2) -> syn::Result<Attributes> {
+    let mut attributes = Attributes { name: None, usage: String::new() };
+
+    let pairs = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attr)?;
+    for pair in pairs {
+        let value = match &pair.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(value), .. }) => value.value(),
+            value => return Err(syn::Error::new(value.span(), "expected a string")),
+        };
+
+        if pair.path.is_ident("name") {
+            attributes.name = Some(value);
+        } else if pair.path.is_ident("usage") {
+            attributes.usage = value;
+        } else {
+            return Err(syn::Error::new(pair.path.span(), "expected `name` or `usage`"));
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// What kind of Rust type a parameter has
+enum Kind {
+    String,
+    Bool,
+    Int(Type),
+    Float(Type),
+}
+
+impl Kind {
+    fn of(ty: &Type) -> syn::Result<Kind> {
+        let name = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last().map(|segment| segment.ident.to_string()),
+            _ => None,
+        };
+
+        match name.as_deref() {
+            Some("String") => Ok(Kind::String),
+            Some("bool") => Ok(Kind::Bool),
+            Some("i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize") => Ok(Kind::Int(ty.clone())),
+            Some("f32" | "f64") => Ok(Kind::Float(ty.clone())),
+            _ => Err(syn::Error::new(ty.span(), "command parameters must be String, bool, an integer, a float, Option<T> or Vec<T>")),
+        }
+    }
+
+    fn param_type(&self) -> TokenStream2 {
+        match self {
+            Kind::String => quote!(::haybcmd::args::ParamType::String),
+            Kind::Bool => quote!(::haybcmd::args::ParamType::Bool),
+            Kind::Int(_) => quote!(::haybcmd::args::ParamType::Int),
+            Kind::Float(_) => quote!(::haybcmd::args::ParamType::Float),
+        }
+    }
+
+    /// Reads the argument at `index` as this type
+    fn extract(&self, name: &str, index: TokenStream2) -> TokenStream2 {
+        match self {
+            Kind::String => quote!(::std::string::String::from(args.str(#index))),
+            Kind::Bool => quote!(args.bool(#index)),
+            Kind::Float(ty) => quote!(args.float(#index) as #ty),
+            Kind::Int(ty) => {
+                let reason = format!("{}: out of range for {}", name, quote!(#ty));
+                quote! {
+                    <#ty as ::std::convert::TryFrom<i64>>::try_from(args.int(#index)).map_err(|_| ::haybcmd::ExecError::InvalidArgument {
+                        command: command.name.clone(),
+                        argument: ::std::string::ToString::to_string(&args.int(#index)),
+                        reason: ::std::string::String::from(#reason),
+                    })?
+                }
+            }
+        }
+    }
+}
+
+/// `T` when `ty` is `wrapper<T>`
+fn inner_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) => match arguments.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn expand(attr: TokenStream2, function: ItemFn) -> syn::Result<TokenStream2> {
+    let attributes = parse_attributes(attr)?;
+
+    let ident = &function.sig.ident;
+    let vis = &function.vis;
+    let name = attributes.name.unwrap_or_else(|| ident.to_string());
+    let usage = attributes.usage;
+
+    let mut inputs = function.sig.inputs.iter();
+    if !matches!(inputs.next(), Some(FnArg::Typed(_))) {
+        return Err(syn::Error::new(function.sig.span(), "the first parameter must take the interpreter"));
+    }
+
+    let mut params = vec!();
+    let mut values = vec!();
+    let mut after_optional = false;
+    let inputs: Vec<&FnArg> = inputs.collect();
+    for (index, input) in inputs.iter().enumerate() {
+        let FnArg::Typed(input) = input else {
+            return Err(syn::Error::new(input.span(), "commands can not take self"));
+        };
+
+        let param_name = match input.pat.as_ref() {
+            syn::Pat::Ident(pat) => pat.ident.to_string(),
+            pat => return Err(syn::Error::new(pat.span(), "expected a parameter name")),
+        };
+
+        if let Some(ty) = inner_type(&input.ty, "Vec") {
+            if index != inputs.len() - 1 {
+                return Err(syn::Error::new(input.ty.span(), "only the last parameter can be a Vec"));
+            }
+
+            let kind = Kind::of(ty)?;
+            let param_type = kind.param_type();
+            let extract = kind.extract(&param_name, quote!(i));
+            params.push(quote!(::haybcmd::args::Param::new(#param_name, #param_type).variadic().optional()));
+            values.push(quote! {{
+                let mut values = ::std::vec::Vec::new();
+                for i in #index..args.len() {
+                    values.push(#extract);
+                }
+                values
+            }});
+        } else if let Some(ty) = inner_type(&input.ty, "Option") {
+            let kind = Kind::of(ty)?;
+            let param_type = kind.param_type();
+            let extract = kind.extract(&param_name, quote!(#index));
+            params.push(quote!(::haybcmd::args::Param::new(#param_name, #param_type).optional()));
+            values.push(quote!(if args.len() > #index { ::core::option::Option::Some(#extract) } else { ::core::option::Option::None }));
+            after_optional = true;
+        } else {
+            // arguments are given in order, so a required one could end up in the Option
+            if after_optional {
+                return Err(syn::Error::new(input.ty.span(), "required parameters can not follow an Option"));
+            }
+
+            let kind = Kind::of(&input.ty)?;
+            let param_type = kind.param_type();
+            let extract = kind.extract(&param_name, quote!(#index));
+            params.push(quote!(::haybcmd::args::Param::new(#param_name, #param_type)));
+            values.push(extract);
+        }
+    }
+
+    let mut inner = function.clone();
+    inner.vis = syn::Visibility::Inherited;
+    inner.attrs.retain(|attr| !attr.path().is_ident("doc"));
+
+    let call = match &function.sig.output {
+        ReturnType::Default => quote!(#ident(interpreter, #(#values),*); ::core::result::Result::Ok(())),
+        ReturnType::Type(..) => quote!(#ident(interpreter, #(#values),*)),
+    };
+
+    let docs = function.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+
+    Ok(quote! {
+        #(#docs)*
+        #[allow(non_camel_case_types)]
+        #vis struct #ident;
+
+        impl ::haybcmd::command::CommandCall for #ident {
+            #[allow(unused_variables)]
+            fn call(&self, interpreter: &mut ::haybcmd::Interpreter, command: &::haybcmd::command::Command, args: &::haybcmd::args::Args) -> ::core::result::Result<(), ::haybcmd::ExecError> {
+                #inner
+                #call
+            }
+        }
+
+        impl ::haybcmd::command::CommandDefinition for #ident {
+            fn command(&self) -> ::haybcmd::command::Command {
+                ::haybcmd::command::Command::with_params(#name, ::std::vec![#(#params),*], #usage)
+            }
+        }
+    })
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use crate::command::CommandsHandler;
+use crate::error::ExecError;
+use crate::value::{Value, ValueType};
+
+/// What a command parameter accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamType {
+    Int,
+    Float,
+    Bool,
+    String,
+    /// One of the listed words
+    Enum(Vec<String>),
+    /// Name of a registered command
+    Command,
+    /// Name of an alias or cvar
+    Variable,
+}
+
+impl fmt::Display for ParamType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamType::Int => write!(f, "int"),
+            ParamType::Float => write!(f, "float"),
+            ParamType::Bool => write!(f, "bool"),
+            ParamType::String => write!(f, "string"),
+            ParamType::Enum(choices) => write!(f, "{}", choices.join("|")),
+            ParamType::Command => write!(f, "command"),
+            ParamType::Variable => write!(f, "variable"),
+        }
+    }
+}
+
+/// A parameter declared by a command. Built with `Param::new` and the<br>
+/// `optional`, `default` and `variadic` modifiers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub param_type: ParamType,
+    pub optional: bool,
+    /// Text used when the argument is not given, converted like a given one
+    pub default: Option<String>,
+    /// Takes every remaining argument. Only allowed on the last parameter
+    pub variadic: bool,
+}
+
+impl Param {
+    pub fn new(name: &str, param_type: ParamType) -> Self {
+        Param {
+            name: name.to_string(),
+            param_type,
+            optional: false,
+            default: None,
+            variadic: false,
+        }
+    }
+
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
+    /// Makes the parameter optional, using `default` when it is not given
+    pub fn default(mut self, default: &str) -> Self {
+        self.optional = true;
+        self.default = Some(default.to_string());
+        self
+    }
+
+    pub fn variadic(mut self) -> Self {
+        self.variadic = true;
+        self
+    }
+
+    /// Checks `text` against the parameter type and converts it
+    pub fn convert(&self, command: &str, text: &str, commands_handler: &CommandsHandler) -> Result<Value, ExecError> {
+        let invalid = |reason: String| ExecError::InvalidArgument {
+            command: command.to_string(),
+            argument: text.to_string(),
+            reason: format!("{}: {}", self.name, reason),
+        };
+
+        let value_type = match &self.param_type {
+            ParamType::Int => ValueType::Int,
+            ParamType::Float => ValueType::Float,
+            ParamType::Bool => ValueType::Bool,
+            ParamType::String => ValueType::String,
+            ParamType::Enum(choices) => {
+                if !choices.iter().any(|choice| choice == text) {
+                    return Err(invalid(format!("expected one of {}", choices.join(", "))));
+                }
+                ValueType::String
+            }
+            ParamType::Command => {
+                if commands_handler.get(text).is_none() {
+                    return Err(ExecError::UnknownCommand(text.to_string()));
+                }
+                ValueType::String
+            }
+            ParamType::Variable => {
+                if !commands_handler.variables.contains_key(text) && !commands_handler.cvars.contains_key(text) {
+                    return Err(ExecError::UnknownVariable(text.to_string()));
+                }
+                ValueType::String
+            }
+        };
+
+        Value::parse(value_type, text).map_err(invalid)
+    }
+}
+
+impl fmt::Display for Param {
+    /// `<name>`, with `:type` unless it is a string, `?` or `?=default` when<br>
+    /// optional and `...` when variadic
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.name)?;
+
+        if self.param_type != ParamType::String {
+            write!(f, ":{}", self.param_type)?;
+        }
+
+        if self.variadic {
+            write!(f, "...")?;
+        }
+
+        if self.optional {
+            write!(f, "?")?;
+        }
+
+        if let Some(default) = &self.default {
+            write!(f, "={}", default)?;
+        }
+
+        write!(f, ">")
+    }
+}
+
+/// Builds the usage line shown by `help`, e.g. `<file?=config.cfg> - saves the config`
+pub fn usage(params: &[Param], description: &str) -> String {
+    let mut usage: Vec<String> = params.iter().map(Param::to_string).collect();
+    usage.push(format!("- {}", description));
+    usage.join(" ")
+}
+
+/// Converts the arguments of a command following its parameters, filling in<br>
+/// defaults. The number of arguments is expected to be already checked
+pub fn convert(command: &str, params: &[Param], args: &[String], commands_handler: &CommandsHandler) -> Result<Args, ExecError> {
+    let mut values = vec!();
+
+    for (i, param) in params.iter().enumerate() {
+        if param.variadic {
+            for arg in &args[i.min(args.len())..] {
+                values.push(param.convert(command, arg, commands_handler)?);
+            }
+            break;
+        }
+
+        match (args.get(i), &param.default) {
+            (Some(arg), _) => values.push(param.convert(command, arg, commands_handler)?),
+            (None, Some(default)) => values.push(param.convert(command, default, commands_handler)?),
+            (None, None) => break,
+        }
+    }
+
+    Ok(Args::new(values))
+}
+
+/// Arguments handed to a command, already converted to the types it declared.<br>
+/// Commands without parameters get every argument as a string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Args {
+    values: Vec<Value>,
+}
+
+impl Args {
+    pub fn new(values: Vec<Value>) -> Self {
+        Args { values }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Value> {
+        self.values.get(index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Value> {
+        self.values.iter()
+    }
+
+    /// The arguments from `index` on, e.g. the values of a variadic parameter
+    pub fn rest(&self, index: usize) -> &[Value] {
+        &self.values[index.min(self.values.len())..]
+    }
+
+    /// Panics if there is no string at `index`, which the declared parameters rule out
+    pub fn str(&self, index: usize) -> &str {
+        match &self.values[index] {
+            Value::String(text) => text,
+            value => panic!("argument {} is a {}, not a string", index, value.value_type()),
+        }
+    }
+
+    /// Panics if there is no int at `index`, which the declared parameters rule out
+    pub fn int(&self, index: usize) -> i64 {
+        match &self.values[index] {
+            Value::Int(int) => *int,
+            value => panic!("argument {} is a {}, not an int", index, value.value_type()),
+        }
+    }
+
+    /// Panics if there is no float at `index`, which the declared parameters rule out
+    pub fn float(&self, index: usize) -> f64 {
+        match &self.values[index] {
+            Value::Float(float) => *float,
+            value => panic!("argument {} is a {}, not a float", index, value.value_type()),
+        }
+    }
+
+    /// Panics if there is no bool at `index`, which the declared parameters rule out
+    pub fn bool(&self, index: usize) -> bool {
+        match &self.values[index] {
+            Value::Bool(bool) => *bool,
+            value => panic!("argument {} is a {}, not a bool", index, value.value_type()),
+        }
+    }
+}
+
+impl From<Vec<String>> for Args {
+    fn from(args: Vec<String>) -> Self {
+        Args::new(args.into_iter().map(Value::String).collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Args {
+    type Item = &'a Value;
+    type IntoIter = std::slice::Iter<'a, Value>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.iter()
+    }
+}
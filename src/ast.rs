@@ -0,0 +1,221 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::config::quote;
+use crate::token::{Span, StringPart};
+
+/// A parsed input: the statements separated by `;`, in order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Script {
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement {
+    /// A command, alias or cvar name followed by its arguments
+    Command(CommandInvocation),
+    /// `if <condition> { ... } else { ... }`
+    If(IfStatement),
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Command(invocation) => invocation.span,
+            Statement::If(statement) => statement.span,
+        }
+    }
+}
+
+/// `name arg1 arg2 ...`. What the name refers to is only decided when it runs,<br>
+/// so a script stays valid when commands, aliases or cvars change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandInvocation {
+    pub name: String,
+    pub name_span: Span,
+    pub args: Vec<Argument>,
+    /// From the name to the last argument
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IfStatement {
+    pub condition: Condition,
+    pub then: Rc<Script>,
+    /// What runs when the condition does not hold. `else if` is a block holding only that `if`
+    pub otherwise: Option<Rc<Script>>,
+    /// From `if` to the last `}`
+    pub span: Span,
+}
+
+/// `left` alone holds unless it is empty, `0` or `false`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub left: Argument,
+    pub comparison: Option<(Comparison, Argument)>,
+}
+
+/// Compares numbers when both sides are numbers and text otherwise
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl Comparison {
+    pub fn from_symbol(symbol: &str) -> Option<Comparison> {
+        match symbol {
+            "==" => Some(Comparison::Equal),
+            "!=" => Some(Comparison::NotEqual),
+            "<" => Some(Comparison::Less),
+            "<=" => Some(Comparison::LessEqual),
+            ">" => Some(Comparison::Greater),
+            ">=" => Some(Comparison::GreaterEqual),
+            _ => None,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+            Comparison::Less => "<",
+            Comparison::LessEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterEqual => ">=",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Argument {
+    pub kind: ArgumentKind,
+    pub span: Span,
+    /// The argument as it was written, quotes and escapes included
+    pub written: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgumentKind {
+    /// Plain word or quoted string, with escapes already resolved
+    Literal(String),
+    /// `$name`, holding the name without the `$`
+    Variable(String),
+    /// Double quoted string with `$name` or `${name}` inside
+    Interpolated(Vec<StringPart>),
+}
+
+/// Words that lex back to the same literal without quotes
+fn is_plain_word(text: &str) -> bool {
+    !text.is_empty()
+        && !text.starts_with(['$', '#'])
+        && !text.starts_with("//")
+        && !text.starts_with("/*")
+        && !text.contains(|c: char| c.is_whitespace() || matches!(c, ';' | '"' | '\''))
+        && text != "{"
+        && text != "}"
+}
+
+/// `quote` without the surrounding double quotes
+fn escape(text: &str) -> String {
+    let quoted = quote(text);
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+impl fmt::Display for Script {
+    /// Writes the statements back as input that parses to the same script
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, statement) in self.statements.iter().enumerate() {
+            if i != 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", statement)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Command(invocation) => write!(f, "{}", invocation),
+            Statement::If(statement) => write!(f, "{}", statement),
+        }
+    }
+}
+
+/// `{ a; b }`, or `{ }` when empty
+fn write_block(f: &mut fmt::Formatter<'_>, block: &Script) -> fmt::Result {
+    if block.statements.is_empty() {
+        write!(f, "{{ }}")
+    } else {
+        write!(f, "{{ {} }}", block)
+    }
+}
+
+impl fmt::Display for IfStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "if {} ", self.condition)?;
+        write_block(f, &self.then)?;
+
+        if let Some(otherwise) = &self.otherwise {
+            write!(f, " else ")?;
+            write_block(f, otherwise)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.left.kind)?;
+
+        if let Some((comparison, right)) = &self.comparison {
+            write!(f, " {} {}", comparison.symbol(), right.kind)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CommandInvocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_plain_word(&self.name) || self.name.starts_with('$') && is_plain_word(&self.name[1..]) {
+            write!(f, "{}", self.name)?;
+        } else {
+            write!(f, "{}", quote(&self.name))?;
+        }
+
+        for arg in &self.args {
+            write!(f, " {}", arg.kind)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ArgumentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgumentKind::Literal(text) if is_plain_word(text) => write!(f, "{}", text),
+            ArgumentKind::Literal(text) => write!(f, "{}", quote(text)),
+            ArgumentKind::Variable(name) => write!(f, "${}", name),
+            ArgumentKind::Interpolated(parts) => {
+                write!(f, "\"")?;
+                for part in parts {
+                    match part {
+                        StringPart::Text(text) => write!(f, "{}", escape(text))?,
+                        StringPart::Variable { name, .. } => write!(f, "${{{}}}", name)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Keys that are not a single printable character, with the codes they get.<br>
+/// Printable characters use their own code, e.g. `w` is 119
+const NAMED_KEYS: &[(&str, u32)] = &[
+    ("SPACE", 32),
+    ("TAB", 256),
+    ("ENTER", 257),
+    ("ESCAPE", 258),
+    ("BACKSPACE", 259),
+    ("UPARROW", 260),
+    ("DOWNARROW", 261),
+    ("LEFTARROW", 262),
+    ("RIGHTARROW", 263),
+    ("INS", 264),
+    ("DEL", 265),
+    ("HOME", 266),
+    ("END", 267),
+    ("PGUP", 268),
+    ("PGDN", 269),
+    ("CAPSLOCK", 270),
+    ("PAUSE", 271),
+    ("SHIFT", 272),
+    ("CTRL", 273),
+    ("ALT", 274),
+    ("RSHIFT", 275),
+    ("RCTRL", 276),
+    ("RALT", 277),
+    ("F1", 280),
+    ("F2", 281),
+    ("F3", 282),
+    ("F4", 283),
+    ("F5", 284),
+    ("F6", 285),
+    ("F7", 286),
+    ("F8", 287),
+    ("F9", 288),
+    ("F10", 289),
+    ("F11", 290),
+    ("F12", 291),
+    ("MOUSE1", 300),
+    ("MOUSE2", 301),
+    ("MOUSE3", 302),
+    ("MOUSE4", 303),
+    ("MOUSE5", 304),
+    ("MWHEELUP", 305),
+    ("MWHEELDOWN", 306),
+];
+
+/// Printable characters that can be bound by themselves
+const PRINTABLE_KEYS: &str = "abcdefghijklmnopqrstuvwxyz0123456789`-=[]\\;',./";
+
+/// A key of the keyboard or a mouse button, named like in Source configs:<br>
+/// `w`, `SPACE`, `SHIFT`, `F1`, `MOUSE1`, `MWHEELUP`...
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Key(u32);
+
+impl Key {
+    /// Finds a key by name, ignoring case
+    pub fn from_name(name: &str) -> Option<Key> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            let c = c.to_ascii_lowercase();
+            return PRINTABLE_KEYS.contains(c).then_some(Key(c as u32));
+        }
+
+        NAMED_KEYS.iter()
+            .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+            .map(|(_, code)| Key(*code))
+    }
+
+    pub fn from_code(code: u32) -> Option<Key> {
+        let named = NAMED_KEYS.iter().any(|(_, key_code)| *key_code == code);
+        let printable = char::from_u32(code).is_some_and(|c| PRINTABLE_KEYS.contains(c));
+
+        (named || printable).then_some(Key(code))
+    }
+
+    pub fn code(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match NAMED_KEYS.iter().find(|(_, code)| *code == self.0) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => write!(f, "{}", char::from_u32(self.0).unwrap_or('?')),
+        }
+    }
+}
+
+/// Which commands keys run. Also remembers what each held key ran so that<br>
+/// releasing it undoes that even if it was rebound in between
+#[derive(Clone, Debug, Default)]
+pub struct Bindings {
+    binds: HashMap<Key, String>,
+    held: HashMap<Key, String>,
+}
+
+impl Bindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `key` to `command`, returning what it was bound to
+    pub fn bind(&mut self, key: Key, command: &str) -> Option<String> {
+        self.binds.insert(key, command.to_string())
+    }
+
+    pub fn unbind(&mut self, key: Key) -> Option<String> {
+        self.binds.remove(&key)
+    }
+
+    pub fn unbind_all(&mut self) {
+        self.binds.clear();
+    }
+
+    pub fn get(&self, key: Key) -> Option<&str> {
+        self.binds.get(&key).map(String::as_str)
+    }
+
+    /// Bindings sorted by key name
+    pub fn sorted(&self) -> Vec<(Key, &str)> {
+        let mut binds: Vec<(Key, &str)> = self.binds.iter().map(|(key, command)| (*key, command.as_str())).collect();
+        binds.sort_by_key(|(key, _)| key.to_string());
+        binds
+    }
+
+    /// What to run for `key` going down, `None` if it is unbound or already held
+    pub(crate) fn press(&mut self, key: Key) -> Option<String> {
+        if self.held.contains_key(&key) {
+            return None;
+        }
+
+        let command = self.binds.get(&key)?.clone();
+        self.held.insert(key, command.clone());
+        Some(command)
+    }
+
+    /// What `key` ran when it went down, if it was held
+    pub(crate) fn release(&mut self, key: Key) -> Option<String> {
+        self.held.remove(&key)
+    }
+}
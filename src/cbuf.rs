@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::ast::Script;
+use crate::error::ExecError;
+use crate::token::Span;
+
+/// A script being run and the index of its next statement. Aliases and<br>
+/// `if` blocks are stacked on top of the script that ran them
+pub(crate) struct Frame {
+    pub(crate) script: Rc<Script>,
+    pub(crate) next: usize,
+    /// Where the input called the alias this runs in, errors inside of it point there
+    pub(crate) call_span: Option<Span>,
+    /// The config file the statements come from. Errors in them are reported<br>
+    /// and the file goes on with its next statement
+    pub(crate) file: Option<Rc<ExecFile>>,
+}
+
+impl Frame {
+    pub(crate) fn new(script: Rc<Script>, call_span: Option<Span>) -> Self {
+        Frame { script, next: 0, call_span, file: None }
+    }
+
+    pub(crate) fn in_file(mut self, file: Option<Rc<ExecFile>>) -> Self {
+        self.file = file;
+        self
+    }
+}
+
+/// A config file being run by `exec`
+pub(crate) struct ExecFile {
+    /// Path as it was found, shown in errors
+    pub(crate) name: String,
+    /// Canonical path, used to find `exec` cycles
+    pub(crate) path: PathBuf,
+}
+
+impl ExecFile {
+    /// Adds the file and the line of `line_span`, used when `error` has no location of its own
+    pub(crate) fn error(&self, error: ExecError, line_span: Span) -> ExecError {
+        ExecError::InFile {
+            file: self.name.clone(),
+            line: error.span().unwrap_or(line_span).line,
+            error: Box::new(error),
+        }
+    }
+}
+
+/// A script stopped by `wait` and how many ticks are left before it goes on
+struct Suspended {
+    frames: Vec<Frame>,
+    ticks: u32,
+}
+
+/// Command buffer holding the scripts waiting for later ticks, see `Interpreter::tick`
+#[derive(Default)]
+pub struct Cbuf {
+    suspended: Vec<Suspended>,
+    /// Ticks asked for by the last `wait`, taken after every statement
+    wait: Option<u32>,
+    /// Script to run before the rest of the current one, taken after every statement
+    inserted: Option<Frame>,
+}
+
+impl Cbuf {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Amount of scripts waiting
+    pub fn len(&self) -> usize {
+        self.suspended.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.suspended.is_empty()
+    }
+
+    /// Drops every waiting script without running the rest of it
+    pub fn clear(&mut self) {
+        self.suspended.clear();
+    }
+
+    /// Asks the script running the current statement to stop for `ticks`
+    pub(crate) fn request_wait(&mut self, ticks: u32) {
+        self.wait = Some(ticks);
+    }
+
+    pub(crate) fn take_wait(&mut self) -> Option<u32> {
+        self.wait.take()
+    }
+
+    /// Asks the script running the current statement to run `frame` before its next statement,<br>
+    /// like Quake's `Cbuf_InsertText`, so that a `wait` in it holds back that script too
+    pub(crate) fn insert(&mut self, frame: Frame) {
+        self.inserted = Some(frame);
+    }
+
+    pub(crate) fn take_inserted(&mut self) -> Option<Frame> {
+        self.inserted.take()
+    }
+
+    pub(crate) fn suspend(&mut self, frames: Vec<Frame>, ticks: u32) {
+        self.suspended.push(Suspended { frames, ticks });
+    }
+
+    /// Counts one tick, returning the scripts that are done waiting in the order they stopped
+    pub(crate) fn advance(&mut self) -> Vec<Vec<Frame>> {
+        let mut ready = vec!();
+
+        self.suspended.retain_mut(|suspended| {
+            suspended.ticks -= 1;
+            if suspended.ticks == 0 {
+                ready.push(std::mem::take(&mut suspended.frames));
+                return false;
+            }
+
+            true
+        });
+
+        ready
+    }
+}
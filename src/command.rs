@@ -1,245 +1,563 @@
 use std::collections::HashMap;
+use std::rc::Rc;
 
-use crate::output;
+use crate::args::{self, Args, Param, ParamType};
+use crate::bindings::{Bindings, Key};
+use crate::cvar::{Cvar, FCVAR_CHEAT, FCVAR_NONE, FCVAR_READONLY};
+use crate::error::ExecError;
+use crate::interpreter::Interpreter;
+use crate::output::OutputSink;
+use crate::parser::KEYWORDS;
+use crate::value::Value;
 
 pub trait CommandCall {
-    fn call(&self, commands_handler: &mut CommandsHandler, command: Box<Command>, args: &[String]);
+    fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError>;
+}
+
+/// A command that carries its own name, parameters and usage, such as<br>
+/// the ones generated by `#[haybcmd::command]`
+pub trait CommandDefinition: CommandCall {
+    fn command(&self) -> Command;
+}
+
+/// Lets closures be registered as commands, see `Interpreter::register`
+impl<F> CommandCall for F
+where
+    F: Fn(&mut Interpreter, &Args) -> Result<(), ExecError>,
+{
+    fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+        self(interpreter, args)
+    }
 }
 
 #[derive(Clone)]
 pub struct Command {
     pub name: String,
-    pub func_idx: usize,
     pub min_args: u8,
     pub max_args: u8,
     pub usage: String,
+    /// Declared parameters. When empty the arguments are passed as strings<br>
+    /// and only their amount is checked
+    pub params: Vec<Param>,
+    /// The last argument takes every word left in the statement as it was written,<br>
+    /// joined by spaces, for commands that parse it again
+    pub rest_of_line: bool,
 }
 
 impl Command {
-    pub fn new(name: &str, func_idx: usize, min_args: u8, max_args: u8, usage: &str) -> Self {
+    pub fn new(name: &str, min_args: u8, max_args: u8, usage: &str) -> Self {
         Command {
             name: name.to_string(),
-            func_idx,
             min_args,
             max_args,
             usage: usage.to_string(),
+            params: vec!(),
+            rest_of_line: false,
         }
     }
-}
 
-pub struct CommandsFuncs {
-    commands_funcs: Vec<Box<dyn CommandCall>>
-}
-
-impl CommandsFuncs {
-    pub fn new() -> Self {
-        CommandsFuncs {
-            commands_funcs: vec!()
+    /// Command whose arguments are checked and converted following `params`<br>
+    /// before it is called. The usage line is generated from them.<br>
+    /// Panics if a required parameter follows an optional one or a variadic one is not the last
+    pub fn with_params(name: &str, params: Vec<Param>, description: &str) -> Self {
+        // arguments are given to parameters in order, so these could never be filled right
+        for (i, param) in params.iter().enumerate() {
+            assert!(!param.variadic || i == params.len() - 1, "{}: only the last parameter can be variadic, not {}", name, param.name);
+            assert!(param.optional || params[..i].iter().all(|previous| !previous.optional), "{}: required parameter {} follows an optional one", name, param.name);
         }
-    }
 
-    pub fn call(&mut self, commands_handler: &mut CommandsHandler, command: Box<Command>, arguments: Vec<String>) {
-        self.commands_funcs[command.func_idx].call(commands_handler, command, &arguments)
-    }
+        let min_args = params.iter().filter(|param| !param.optional).count() as u8;
+        let max_args = if params.last().is_some_and(|param| param.variadic) { u8::MAX } else { params.len() as u8 };
+
+        let usage = args::usage(&params, description);
 
-    pub fn push(&mut self, command_func: impl CommandCall + 'static) {
-        self.commands_funcs.push(Box::new(command_func));
+        Command {
+            params,
+            ..Command::new(name, min_args, max_args, &usage)
+        }
     }
 
-    pub fn get(&self) -> Vec<Box<dyn CommandCall>> {
-        let mut v = vec!();
-        for i in self.commands_funcs {
-            v.push(i);
+    /// Checks and converts the arguments following `params`
+    pub fn convert_args(&self, args: &[String], commands_handler: &CommandsHandler) -> Result<Args, ExecError> {
+        if self.params.is_empty() {
+            return Ok(Args::from(args.to_vec()));
         }
 
-        v
+        args::convert(&self.name, &self.params, args, commands_handler)
+    }
+
+    /// Makes the last argument take the rest of the statement, so that<br>
+    /// e.g. `alias greet echo "a;b"` gets a single `echo "a;b"` argument
+    pub fn with_rest_of_line(mut self) -> Self {
+        self.rest_of_line = true;
+        self
     }
 }
 
-#[derive(Clone)]
+/// A registered command kept together with the function it calls. Both are<br>
+/// reference counted so that they can be kept while the interpreter is borrowed by the function
+struct Registered {
+    command: Rc<Command>,
+    func: Rc<dyn CommandCall>,
+}
+
 pub struct CommandsHandler {
-    pub commands: Vec<Command>,
-    pub variables: HashMap<String, String>
+    /// Commands by name, only added through `register`
+    commands: HashMap<String, Registered>,
+    pub variables: HashMap<String, String>,
+    pub cvars: HashMap<String, Cvar>,
+    pub bindings: Bindings,
+}
+
+impl Default for CommandsHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CommandsHandler {
     pub fn new() -> Self {
         CommandsHandler{
-            commands: vec!(),
-            variables: HashMap::new()
+            commands: HashMap::new(),
+            variables: HashMap::new(),
+            cvars: HashMap::new(),
+            bindings: Bindings::new(),
         }
     }
 
     /// Deletes a command
     pub fn delete(&mut self, command_name: &str) -> bool {
-        for (i, command) in self.commands.iter().enumerate() {
-            if command.name == command_name {
-                self.commands.remove(i);
-                return true;
-            }
-        }
-
-        false
+        self.commands.remove(command_name).is_some()
     }
 
     /// Gets a command
-    pub fn get<S: ToString>(&mut self, name: S, print_error: bool) -> Option<Box<Command>> {
-        for command in self.commands.iter() {
-            if command.name == name.to_string() {
-                return Some(Box::new(command.clone()));
-            }
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.get(name).map(|registered| registered.command.as_ref())
+    }
+
+    /// Gets a command and its function so that they can outlive a borrow of this handler, used to call it
+    pub fn get_shared(&self, name: &str) -> Option<(Rc<Command>, Rc<dyn CommandCall>)> {
+        self.commands.get(name).map(|registered| (registered.command.clone(), registered.func.clone()))
+    }
+
+    /// Every command, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Command> {
+        self.commands.values().map(|registered| registered.command.as_ref())
+    }
+
+    /// Finds if a command with the same name is registered
+    pub fn contains(&self, this_command: &Command) -> bool {
+        self.commands.contains_key(&this_command.name)
+    }
+
+    /// The `-name` command or alias run when a key holding `+name` is released,<br>
+    /// if there is one
+    pub fn release_of(&self, name: &str) -> Option<String> {
+        let release = format!("-{}", name.strip_prefix('+')?);
+
+        if self.commands.contains_key(&release) || self.variables.contains_key(&release) {
+            Some(release)
+        } else {
+            None
         }
+    }
+
+    /// Commands sorted by name
+    pub fn sorted(&self) -> Vec<&Command> {
+        let mut commands: Vec<&Command> = self.iter().collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        commands
+    }
 
-        if print_error {
-            output::print(&format!("unknown command \"{}\"\n", name.to_string()));
+    /// Registers a new command together with its function so that the command<br>
+    /// always calls it. Returns false if the name is already taken by a command or cvar, or is a keyword
+    pub fn register(&mut self, command: Command, func: impl CommandCall + 'static) -> bool {
+        if self.contains(&command) || self.cvars.contains_key(&command.name) || KEYWORDS.contains(&command.name.as_str()) {
+            return false;
         }
 
-        None
+        let registered = Registered {
+            command: Rc::new(command),
+            func: Rc::new(func),
+        };
+        self.commands.insert(registered.command.name.clone(), registered);
+        true
     }
 
-    /// Finds if a command is inside commands vector
-    pub fn contains(&self, this_command: &Command) -> bool {
-        for command in self.commands.iter() {
-            if command.name == this_command.name {
-                return true;
-            }
+    /// Registers a new cvar whose type is the type of `default`.<br>
+    /// Returns `None` if the name is already taken by a command or cvar, or is a keyword
+    pub fn register_cvar(&mut self, name: &str, default: impl Into<Value>, help: &str, flags: u32) -> Option<&mut Cvar> {
+        if self.cvars.contains_key(name) || self.get(name).is_some() || KEYWORDS.contains(&name) {
+            return None;
         }
 
-        false
+        self.cvars.insert(name.to_string(), Cvar::new(name, default.into(), help, flags));
+        self.cvars.get_mut(name)
+    }
+
+    /// Gets a cvar
+    pub fn get_cvar(&self, name: &str) -> Option<&Cvar> {
+        self.cvars.get(name)
     }
 
-    /// Registers a new commands
-    pub fn register(&mut self, command: Command) {
-        if self.contains(&command) {
-            return;
+    /// Gets a cvar mutably, e.g. to add change callbacks
+    pub fn get_cvar_mut(&mut self, name: &str) -> Option<&mut Cvar> {
+        self.cvars.get_mut(name)
+    }
+
+    /// Sets a cvar from Rust code. Flags are not checked but the value<br>
+    /// is still converted to the cvar type, clamped and passed to the callbacks
+    pub fn set_cvar(&mut self, name: &str, value: impl Into<Value>) -> Result<(), ExecError> {
+        let value = value.into();
+        let cvar = self.cvars.get_mut(name).ok_or_else(|| ExecError::UnknownVariable(name.to_string()))?;
+
+        cvar.set(value.clone()).map_err(|reason| ExecError::InvalidArgument {
+            command: name.to_string(),
+            argument: value.to_string(),
+            reason,
+        })
+    }
+
+    /// Sets a cvar the way the console does, refusing read only cvars<br>
+    /// and cheat cvars while `sv_cheats` is off
+    pub fn set_cvar_from_console(&mut self, name: &str, text: &str) -> Result<(), ExecError> {
+        let cvar = self.cvars.get(name).ok_or_else(|| ExecError::UnknownVariable(name.to_string()))?;
+
+        if cvar.has_flag(FCVAR_READONLY) {
+            return Err(ExecError::ReadOnlyCvar(name.to_string()));
+        }
+
+        if cvar.has_flag(FCVAR_CHEAT) && !self.cvars.get("sv_cheats").is_some_and(|cheats| cheats.value().as_bool()) {
+            return Err(ExecError::CheatProtected(name.to_string()));
         }
 
-        self.commands.push(command);
+        self.set_cvar(name, text)
     }
 }
 
-pub fn init_base_commands(commands_handler: &mut CommandsHandler, commands_funcs: &mut CommandsFuncs) {
+pub fn init_base_commands(commands_handler: &mut CommandsHandler) {
     struct HelpFunc;
     impl CommandCall for HelpFunc {
-        fn call(&self, commands_handler: &mut CommandsHandler, _command: Box<Command>, args: &[String]) {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
             if args.len() == 1 {
-                // Print usage for a specific command
-                if let Some(command) = commands_handler.get(&args[0], true) {
-                    output::print_command_usage(command.name.clone(), command.usage.clone());
+                // Print usage for a specific command or the description of a cvar
+                if let Some(cvar) = interpreter.commands_handler.get_cvar(args.str(0)) {
+                    interpreter.output.println(&cvar.describe());
+                    return Ok(());
                 }
-                return;
+
+                let command = interpreter.commands_handler.get(args.str(0)).ok_or_else(|| ExecError::UnknownCommand(args.str(0).to_string()))?;
+                interpreter.output.print_command_usage(&command.name, &command.usage);
+                return Ok(());
             }
     
             // Print usage for all commands
-            for command in commands_handler.commands.iter() {
-                output::print_command_usage(command.name.clone(), command.usage.clone());
+            for command in interpreter.commands_handler.sorted() {
+                interpreter.output.print_command_usage(&command.name, &command.usage);
             }
+
+            Ok(())
         }
     }
 
     struct EchoFunc;
     impl CommandCall for EchoFunc {
-        fn call(&self, _commands_handler: &mut CommandsHandler, _command: Box<Command>, args: &[String]) {
-            let message = args.join(" ");
-            output::println(&format!("{}", message));
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+            let message: Vec<String> = args.iter().map(Value::to_string).collect();
+            let message = message.join(" ");
+            interpreter.output.println(&message);
+            Ok(())
         }
     }
 
     struct AliasFunc;
     impl CommandCall for AliasFunc {
-        fn call(&self, commands_handler: &mut CommandsHandler, _command: Box<Command>, args: &[String]) {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let name = args.str(0);
             if args.len() == 1 {
-                commands_handler.variables.remove(&args[0]);
-                return;
+                interpreter.commands_handler.variables.remove(name);
+                return Ok(());
             }
     
-            if commands_handler.get(&args[0], false).is_some() {
-                output::print("varName is a command name, therefore this variable can not be created\n");
-                return;
+            if interpreter.commands_handler.get(name).is_some() || interpreter.commands_handler.get_cvar(name).is_some() {
+                return Err(ExecError::InvalidArgument {
+                    command: command.name.clone(),
+                    argument: name.to_string(),
+                    reason: "varName is a command or cvar name, therefore this variable can not be created".to_string(),
+                });
+            }
+
+            if KEYWORDS.contains(&name) {
+                return Err(ExecError::InvalidArgument {
+                    command: command.name.clone(),
+                    argument: name.to_string(),
+                    reason: "varName is a keyword, therefore this variable can not be created".to_string(),
+                });
             }
     
-            if args[0].chars().map(|c| {c.is_whitespace() || c.is_ascii_whitespace()}).count() > 0 {
-                output::print("variable name can not have whitespace.\n");
-                return;
+            if name.chars().any(|c| c.is_whitespace()) {
+                return Err(ExecError::InvalidArgument {
+                    command: command.name.clone(),
+                    argument: name.to_string(),
+                    reason: "variable name can not have whitespace".to_string(),
+                });
             }
     
-            commands_handler.variables.insert(args[0].clone(), args[1].clone());
+            interpreter.commands_handler.variables.insert(name.to_string(), args.str(1).to_string());
+            Ok(())
         }
     }
 
     struct GetVariablesFunc;
     impl CommandCall for GetVariablesFunc {
-        fn call(&self, commands_handler: &mut CommandsHandler, _command: Box<Command>, _args: &[String]) {
-            let mut output = String::new();
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, _args: &Args) -> Result<(), ExecError> {
+            let mut list = String::new();
             let mut count = 0;
 
-            for (key, value) in commands_handler.variables.iter() {
-                output += &format!("{} = \"{}\"\n", key, value);
+            for (key, value) in interpreter.commands_handler.variables.iter() {
+                list += &format!("{} = \"{}\"\n", key, value);
                 count += 1;
             }
 
             let mut out = format!("amount of variables: {}\n", count);
-            if !output.is_empty() {
-                output.pop(); // Remove trailing newline
-                out += &format!("{}\n", output);
+            if !list.is_empty() {
+                list.pop(); // Remove trailing newline
+                out += &format!("{}\n", list);
             }
 
-            output::print(&out);
+            interpreter.output.print(&out);
+            Ok(())
         }
     }
 
     struct VariableFunc;
     impl CommandCall for VariableFunc {
-        fn call(&self, commands_handler: &mut CommandsHandler, _command: Box<Command>, args: &[String]) {
-            let key = &args[0];
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+            let key = args.str(0);
             
-            if let Some(value) = commands_handler.variables.get(key) {
-                output::print(&format!("{} = \"{}\"\n", key, value));
-            } else {
-                output::print(&format!("variable \"{}\" does not exist\n", key));
-            }
+            let value = interpreter.commands_handler.variables.get(key).ok_or_else(|| ExecError::UnknownVariable(key.to_string()))?;
+            interpreter.output.print(&format!("{} = \"{}\"\n", key, value));
+            Ok(())
         }
     }
 
     struct IncrementVarFunc;
     impl CommandCall for IncrementVarFunc {
-        fn call(&self, commands_handler: &mut CommandsHandler, _command: Box<Command>, args: &[String]) {
-            let variable = &args[0];
-            let min_value = args[1].parse::<f64>().unwrap();
-            let max_value = args[2].parse::<f64>().unwrap();
-            let delta = args[3].parse::<f64>().unwrap();
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let variable = args.str(0);
+            let min_value = args.float(1);
+            let max_value = args.float(2);
+            let delta = args.float(3);
 
             if min_value > max_value {
-                output::print("minValue is higher than maxValue");
-                return;
+                return Err(ExecError::InvalidArgument {
+                    command: command.name.clone(),
+                    argument: min_value.to_string(),
+                    reason: "minValue is higher than maxValue".to_string(),
+                });
             }
 
-            if let Some(value) = commands_handler.variables.get_mut(variable) {
-                let mut variable_value = value.parse::<f64>().unwrap();
+            let wrap = |mut variable_value: f64| {
                 variable_value += delta;
                 if variable_value > max_value {
                     variable_value = min_value;
                 } else if variable_value < min_value {
                     variable_value = max_value;
                 }
-                *value = variable_value.to_string();
-            } else {
-                output::print(&format!("unknown variable \"{}\"\n", variable));
+                variable_value
+            };
+
+            if let Some(cvar) = interpreter.commands_handler.get_cvar(variable) {
+                let current = cvar.value().as_f64().ok_or_else(|| ExecError::InvalidArgument {
+                    command: command.name.clone(),
+                    argument: variable.to_string(),
+                    reason: "cvar is not numeric".to_string(),
+                })?;
+                return interpreter.commands_handler.set_cvar_from_console(variable, &wrap(current).to_string());
+            }
+
+            let value = interpreter.commands_handler.variables.get_mut(variable).ok_or_else(|| ExecError::UnknownVariable(variable.to_string()))?;
+            let current = value.parse::<f64>().map_err(|_| ExecError::InvalidArgument {
+                command: command.name.clone(),
+                argument: variable.to_string(),
+                reason: format!("\"{}\" is not a number", value),
+            })?;
+            *value = wrap(current).to_string();
+            Ok(())
+        }
+    }
+
+    struct CvarListFunc;
+    impl CommandCall for CvarListFunc {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, _args: &Args) -> Result<(), ExecError> {
+            let mut cvars: Vec<&Cvar> = interpreter.commands_handler.cvars.values().collect();
+            cvars.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let mut out = format!("amount of cvars: {}\n", cvars.len());
+            for cvar in cvars {
+                out += &format!("{}\n", cvar.describe());
+            }
+
+            interpreter.output.print(&out);
+            Ok(())
+        }
+    }
+
+    struct ExecFunc;
+    impl CommandCall for ExecFunc {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+            let frame = interpreter.load_file(args.str(0))?;
+            interpreter.cbuf.insert(frame);
+            Ok(())
+        }
+    }
+
+    struct WriteConfigFunc;
+    impl CommandCall for WriteConfigFunc {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+            interpreter.write_config(args.str(0))
+        }
+    }
+
+    struct WaitFunc;
+    impl CommandCall for WaitFunc {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let ticks = u32::try_from(args.int(0)).map_err(|_| ExecError::InvalidArgument {
+                command: command.name.clone(),
+                argument: args.int(0).to_string(),
+                reason: if args.int(0) < 0 { "ticks: can not be negative".to_string() } else { format!("ticks: can not be more than {}", u32::MAX) },
+            })?;
+
+            interpreter.cbuf.request_wait(ticks);
+            Ok(())
+        }
+    }
+
+    fn seconds(command: &Command, args: &Args, i: usize, name: &str) -> Result<f64, ExecError> {
+        let seconds = args.float(i);
+        if !seconds.is_finite() || seconds < 0.0 {
+            let reason = if seconds.is_finite() { "can not be negative" } else { "must be a finite number" };
+            return Err(ExecError::InvalidArgument {
+                command: command.name.clone(),
+                argument: seconds.to_string(),
+                reason: format!("{}: {}", name, reason),
+            });
+        }
+
+        Ok(seconds)
+    }
+
+    struct DelayFunc;
+    impl CommandCall for DelayFunc {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let seconds = seconds(command, args, 0, "seconds")?;
+            // locations inside the commands mean nothing to the caller
+            let script = interpreter.parse(args.str(1)).map_err(ExecError::without_span)?;
+
+            interpreter.timers.delay(seconds, script);
+            Ok(())
+        }
+    }
+
+    struct RepeatFunc;
+    impl CommandCall for RepeatFunc {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let interval = seconds(command, args, 0, "interval")?;
+            let count = u32::try_from(args.int(1)).map_err(|_| ExecError::InvalidArgument {
+                command: command.name.clone(),
+                argument: args.int(1).to_string(),
+                reason: if args.int(1) < 0 { "count: can not be negative".to_string() } else { format!("count: can not be more than {}", u32::MAX) },
+            })?;
+            // locations inside the commands mean nothing to the caller
+            let script = interpreter.parse(args.str(2)).map_err(ExecError::without_span)?;
+
+            interpreter.timers.repeat(interval, count, script);
+            Ok(())
+        }
+    }
+
+    fn parse_key(command: &Command, name: &str) -> Result<Key, ExecError> {
+        Key::from_name(name).ok_or_else(|| ExecError::InvalidArgument {
+            command: command.name.clone(),
+            argument: name.to_string(),
+            reason: "unknown key".to_string(),
+        })
+    }
+
+    struct BindFunc;
+    impl CommandCall for BindFunc {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let key = parse_key(command, args.str(0))?;
+
+            if args.len() == 2 {
+                interpreter.commands_handler.bindings.bind(key, args.str(1));
+                return Ok(());
+            }
+
+            match interpreter.commands_handler.bindings.get(key) {
+                Some(bound) => interpreter.output.print(&format!("{} = \"{}\"\n", key, bound)),
+                None => interpreter.output.print(&format!("{} is not bound\n", key)),
             }
+            Ok(())
+        }
+    }
+
+    struct UnbindFunc;
+    impl CommandCall for UnbindFunc {
+        fn call(&self, interpreter: &mut Interpreter, command: &Command, args: &Args) -> Result<(), ExecError> {
+            let key = parse_key(command, args.str(0))?;
+            interpreter.commands_handler.bindings.unbind(key);
+            Ok(())
+        }
+    }
+
+    struct UnbindAllFunc;
+    impl CommandCall for UnbindAllFunc {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, _args: &Args) -> Result<(), ExecError> {
+            interpreter.commands_handler.bindings.unbind_all();
+            Ok(())
+        }
+    }
+
+    struct BindListFunc;
+    impl CommandCall for BindListFunc {
+        fn call(&self, interpreter: &mut Interpreter, _command: &Command, _args: &Args) -> Result<(), ExecError> {
+            let binds = interpreter.commands_handler.bindings.sorted();
+
+            let mut out = format!("amount of binds: {}\n", binds.len());
+            for (key, bound) in binds {
+                out += &format!("{} = \"{}\"\n", key, bound);
+            }
+
+            interpreter.output.print(&out);
+            Ok(())
         }
     }
 
     // Add commands
-    commands_handler.register(Command::new("help", 0, 0, 1, "<command?> - shows a list of commands usages or the usage of a specific command"));
-    commands_funcs.push(HelpFunc);
-    commands_handler.register(Command::new("echo", 1, 1, 1, "<message> - echoes a message to the console"));
-    commands_funcs.push(EchoFunc);
-    commands_handler.register(Command::new("alias", 2, 1, 2, "<var> <commands?> - creates/deletes variables"));
-    commands_funcs.push(AliasFunc);
-    commands_handler.register(Command::new("variables", 3, 0, 0, "- list of variables"));
-    commands_funcs.push(GetVariablesFunc);
-    commands_handler.register(Command::new("variable", 4, 1, 1, "- shows variable value"));
-    commands_funcs.push(VariableFunc);
-    commands_handler.register(Command::new("incrementvar", 5, 4, 4, "<var> <minValue> <maxValue> <delta> - increments the value of a variable"));
-    commands_funcs.push(IncrementVarFunc);
-}
\ No newline at end of file
+    commands_handler.register(Command::with_params("help", vec![Param::new("command", ParamType::String).optional()], "shows a list of commands usages or the usage of a specific command"), HelpFunc);
+    commands_handler.register(Command::with_params("echo", vec![Param::new("message", ParamType::String).variadic()], "echoes a message to the console"), EchoFunc);
+    commands_handler.register(Command::with_params("alias", vec![Param::new("var", ParamType::String), Param::new("commands", ParamType::String).optional()], "creates/deletes variables").with_rest_of_line(), AliasFunc);
+    commands_handler.register(Command::with_params("variables", vec!(), "list of variables"), GetVariablesFunc);
+    commands_handler.register(Command::with_params("variable", vec![Param::new("var", ParamType::String)], "shows variable value"), VariableFunc);
+    commands_handler.register(Command::with_params("incrementvar", vec![
+        Param::new("var", ParamType::Variable),
+        Param::new("minValue", ParamType::Float),
+        Param::new("maxValue", ParamType::Float),
+        Param::new("delta", ParamType::Float),
+    ], "increments the value of a variable"), IncrementVarFunc);
+    commands_handler.register(Command::with_params("cvarlist", vec!(), "list of cvars"), CvarListFunc);
+    commands_handler.register(Command::with_params("exec", vec![Param::new("file", ParamType::String)], "runs every line of a config file"), ExecFunc);
+    commands_handler.register(Command::with_params("host_writeconfig", vec![Param::new("file", ParamType::String).default("config.cfg")], "saves aliases, binds and archived cvars"), WriteConfigFunc);
+    commands_handler.register(Command::with_params("wait", vec![Param::new("ticks", ParamType::Int).default("1")], "runs the rest of the script ticks later"), WaitFunc);
+    commands_handler.register(Command::with_params("delay", vec![Param::new("seconds", ParamType::Float), Param::new("commands", ParamType::String)], "runs commands after some seconds").with_rest_of_line(), DelayFunc);
+    commands_handler.register(Command::with_params("repeat", vec![Param::new("interval", ParamType::Float), Param::new("count", ParamType::Int), Param::new("commands", ParamType::String)], "runs commands count times, every interval seconds").with_rest_of_line(), RepeatFunc);
+    commands_handler.register(Command::with_params("bind", vec![Param::new("key", ParamType::String), Param::new("command", ParamType::String).optional()], "binds a key to commands or shows its binding").with_rest_of_line(), BindFunc);
+    commands_handler.register(Command::with_params("unbind", vec![Param::new("key", ParamType::String)], "removes the binding of a key"), UnbindFunc);
+    commands_handler.register(Command::with_params("unbindall", vec!(), "removes every binding"), UnbindAllFunc);
+    commands_handler.register(Command::with_params("bindlist", vec!(), "list of binds"), BindListFunc);
+
+    // Add cvars
+    commands_handler.register_cvar("developer", 0, "shows developer messages up to this level", FCVAR_NONE).unwrap().set_bounds(Some(0.0), Some(255.0)).unwrap();
+    commands_handler.register_cvar("sv_cheats", false, "allows changing cheat protected cvars", FCVAR_NONE);
+}
@@ -0,0 +1,52 @@
+use crate::command::CommandsHandler;
+use crate::cvar::{FCVAR_ARCHIVE, FCVAR_CHEAT, FCVAR_READONLY};
+
+/// Wraps `text` in double quotes, escaping what the lexer would<br>
+/// otherwise read differently
+pub fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' | '\\' | '$' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Serializes the user aliases, the key bindings and the archived cvars into commands<br>
+/// that restore them when executed, sorted by name so the output is stable.<br>
+/// Read only and cheat protected cvars are skipped because they could<br>
+/// not be set back from a config file
+pub fn write_config(commands_handler: &CommandsHandler) -> String {
+    let mut out = String::new();
+
+    let mut aliases: Vec<(&String, &String)> = commands_handler.variables.iter().collect();
+    aliases.sort();
+    for (name, value) in aliases {
+        out += &format!("alias {} {}\n", quote(name), quote(value));
+    }
+
+    for (key, command) in commands_handler.bindings.sorted() {
+        out += &format!("bind {} {}\n", quote(&key.to_string()), quote(command));
+    }
+
+    let mut cvars: Vec<_> = commands_handler.cvars.values()
+        .filter(|cvar| cvar.has_flag(FCVAR_ARCHIVE) && !cvar.has_flag(FCVAR_READONLY | FCVAR_CHEAT))
+        .collect();
+    cvars.sort_by(|a, b| a.name.cmp(&b.name));
+    for cvar in cvars {
+        out += &format!("{} {}\n", cvar.name, quote(&cvar.value().to_string()));
+    }
+
+    out
+}
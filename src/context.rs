@@ -0,0 +1,50 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+
+use crate::error::ExecError;
+
+/// Application state handed to commands through the interpreter, holding<br>
+/// at most one value per type, e.g. the player, the renderer or the sound system
+#[derive(Default)]
+pub struct Context {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `value`, returning the previous value of the same type
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values.insert(TypeId::of::<T>(), Box::new(value))
+            .map(|previous| *previous.downcast::<T>().unwrap())
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>())
+            .map(|value| *value.downcast::<T>().unwrap())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>()).and_then(|value| value.downcast_mut())
+    }
+
+    /// Same as `get` but fails with `ExecError::MissingContext` so commands can use `?`
+    pub fn require<T: 'static>(&self) -> Result<&T, ExecError> {
+        self.get().ok_or_else(|| ExecError::MissingContext(type_name::<T>().to_string()))
+    }
+
+    /// Same as `get_mut` but fails with `ExecError::MissingContext` so commands can use `?`
+    pub fn require_mut<T: 'static>(&mut self) -> Result<&mut T, ExecError> {
+        self.get_mut().ok_or_else(|| ExecError::MissingContext(type_name::<T>().to_string()))
+    }
+}
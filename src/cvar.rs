@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::value::{Value, ValueType};
+
+/// No special behavior
+pub const FCVAR_NONE: u32 = 0;
+/// Saved when the config is written
+pub const FCVAR_ARCHIVE: u32 = 1 << 0;
+/// Can only be changed from the console while `sv_cheats` is on
+pub const FCVAR_CHEAT: u32 = 1 << 1;
+/// Can not be changed from the console, only from Rust code
+pub const FCVAR_READONLY: u32 = 1 << 2;
+
+/// Called with the old and the proposed new value before a cvar changes.<br>
+/// It can clamp by modifying the new value or veto the change by returning `Err(reason)`
+pub type CvarCallback = Box<dyn FnMut(&Value, &mut Value) -> Result<(), String>>;
+
+/// A typed console variable. Its type is the type of the default value
+pub struct Cvar {
+    pub name: String,
+    pub help: String,
+    pub flags: u32,
+    default: Value,
+    value: Value,
+    min: Option<f64>,
+    max: Option<f64>,
+    callbacks: Vec<CvarCallback>,
+}
+
+impl Cvar {
+    pub fn new(name: &str, default: Value, help: &str, flags: u32) -> Self {
+        Cvar {
+            name: name.to_string(),
+            help: help.to_string(),
+            flags,
+            value: default.clone(),
+            default,
+            min: None,
+            max: None,
+            callbacks: vec!(),
+        }
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn default_value(&self) -> &Value {
+        &self.default
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.default.value_type()
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    pub fn has_flag(&self, flag: u32) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// Limits numeric cvars to `[min, max]` and clamps the current value, which goes<br>
+    /// through the callbacks like any other change. If one of them vetoes it the bounds stay as they were
+    pub fn set_bounds(&mut self, min: Option<f64>, max: Option<f64>) -> Result<&mut Self, String> {
+        let (old_min, old_max) = (self.min, self.max);
+        self.min = min;
+        self.max = max;
+
+        if let Err(reason) = self.set(self.value.clone()) {
+            self.min = old_min;
+            self.max = old_max;
+            return Err(reason);
+        }
+
+        Ok(self)
+    }
+
+    /// Converts `value` to this cvar's type and clamps it to the bounds
+    pub fn coerce(&self, value: Value) -> Result<Value, String> {
+        let value = if value.value_type() == self.value_type() {
+            value
+        } else {
+            Value::parse(self.value_type(), &value.to_string())?
+        };
+
+        Ok(self.clamp(value))
+    }
+
+    /// Adds a listener that runs every time the value is about to change,<br>
+    /// no matter if it was set from the console, a config file or Rust code
+    pub fn add_callback(&mut self, callback: impl FnMut(&Value, &mut Value) -> Result<(), String> + 'static) -> &mut Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    fn clamp(&self, value: Value) -> Value {
+        match value {
+            Value::Int(mut i) => {
+                if let Some(min) = self.min {
+                    i = i.max(min.ceil() as i64);
+                }
+                if let Some(max) = self.max {
+                    i = i.min(max.floor() as i64);
+                }
+                Value::Int(i)
+            }
+            Value::Float(mut f) => {
+                if let Some(min) = self.min {
+                    f = f.max(min);
+                }
+                if let Some(max) = self.max {
+                    f = f.min(max);
+                }
+                Value::Float(f)
+            }
+            other => other,
+        }
+    }
+
+    /// Converts, clamps and runs the callbacks before storing the value.<br>
+    /// Callbacks only run when the value actually changes
+    pub(crate) fn set(&mut self, value: Value) -> Result<(), String> {
+        let mut new_value = self.coerce(value)?;
+        if new_value == self.value {
+            return Ok(());
+        }
+
+        for callback in self.callbacks.iter_mut() {
+            callback(&self.value, &mut new_value)?;
+        }
+
+        // a callback may have replaced the value with anything
+        self.value = self.coerce(new_value)?;
+        Ok(())
+    }
+
+    /// One line summary used when the cvar is queried from the console
+    pub fn describe(&self) -> String {
+        let mut out = format!("{} = \"{}\" (def. \"{}\"", self.name, self.value, self.default);
+        if let Some(min) = self.min {
+            out += &format!(", min. {}", min);
+        }
+        if let Some(max) = self.max {
+            out += &format!(", max. {}", max);
+        }
+        out.push(')');
+
+        if !self.help.is_empty() {
+            out += &format!(" - {}", self.help);
+        }
+
+        out
+    }
+}
+
+impl fmt::Debug for Cvar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cvar")
+            .field("name", &self.name)
+            .field("help", &self.help)
+            .field("flags", &self.flags)
+            .field("default", &self.default)
+            .field("value", &self.value)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .field("callbacks", &self.callbacks.len())
+            .finish()
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use crate::token::Span;
+
+/// Everything that can go wrong while lexing, parsing or running a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecError {
+    /// The first word of a statement is neither a command nor a variable
+    UnknownCommand(String),
+    /// A variable that was asked for does not exist
+    UnknownVariable(String),
+    /// The command got fewer or more arguments than it accepts
+    BadArity {
+        command: String,
+        usage: String,
+        min: u8,
+        max: u8,
+        given: usize,
+    },
+    /// The command rejected one of its arguments
+    InvalidArgument {
+        command: String,
+        argument: String,
+        reason: String,
+    },
+    /// The cvar is flagged `FCVAR_READONLY`
+    ReadOnlyCvar(String),
+    /// The cvar is flagged `FCVAR_CHEAT` and `sv_cheats` is off
+    CheatProtected(String),
+    /// Aliases kept expanding into other aliases past the allowed amount of calls
+    AliasRecursionLimit(usize),
+    /// A config file could not be found or read
+    Io {
+        path: String,
+        reason: String,
+    },
+    /// A config file ended up executing itself
+    ExecCycle(String),
+    /// An error that happened while running a line of a config file
+    InFile {
+        file: String,
+        line: usize,
+        error: Box<ExecError>,
+    },
+    /// An error located at a part of the input, e.g. the argument that was rejected
+    At {
+        span: Span,
+        error: Box<ExecError>,
+    },
+    /// A string was opened with `"` or `'` but never closed
+    UnterminatedString,
+    /// A malformed `\u{..}` escape inside a string
+    InvalidEscape(String),
+    /// A `/*` comment was never closed
+    UnterminatedComment,
+    /// The input does not follow the grammar, e.g. an `if` without its block
+    Syntax(String),
+    /// A command needs a type of application state that was not put in the interpreter context
+    MissingContext(String),
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecError::UnknownCommand(name) => write!(f, "unknown command \"{}\"", name),
+            ExecError::UnknownVariable(name) => write!(f, "unknown variable \"{}\"", name),
+            ExecError::BadArity { command, usage, min, max, given } => write!(
+                f,
+                "{} {}\narguments size must be within range [{}, {}], but size is {}",
+                command, usage, min, max, given
+            ),
+            ExecError::InvalidArgument { command, argument, reason } => {
+                write!(f, "{}: invalid argument \"{}\": {}", command, argument, reason)
+            }
+            ExecError::ReadOnlyCvar(name) => write!(f, "\"{}\" is read only", name),
+            ExecError::CheatProtected(name) => write!(f, "\"{}\" is cheat protected, sv_cheats must be enabled", name),
+            ExecError::AliasRecursionLimit(limit) => {
+                write!(f, "alias expansion exceeded the limit of {} calls without a wait", limit)
+            }
+            ExecError::Io { path, reason } => write!(f, "couldn't exec \"{}\": {}", path, reason),
+            ExecError::ExecCycle(path) => write!(f, "\"{}\" is already being executed", path),
+            ExecError::InFile { file, line, error } => match error.as_ref() {
+                ExecError::At { span, error } => write!(f, "{}:{}:{}: {}", file, line, span.column, error),
+                error => write!(f, "{}:{}: {}", file, line, error),
+            },
+            ExecError::At { span, error } => write!(f, "{}: {}", span, error),
+            ExecError::UnterminatedString => write!(f, "unterminated string"),
+            ExecError::InvalidEscape(reason) => write!(f, "invalid escape: {}", reason),
+            ExecError::UnterminatedComment => write!(f, "unterminated comment"),
+            ExecError::Syntax(reason) => write!(f, "syntax error: {}", reason),
+            ExecError::MissingContext(type_name) => write!(f, "no {} in the interpreter context", type_name),
+        }
+    }
+}
+
+impl ExecError {
+    /// Attaches where the error happened, unless it already has a location
+    pub fn at(self, span: Span) -> ExecError {
+        match self {
+            ExecError::At { .. } => self,
+            error => ExecError::At {
+                span,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Where in the executed input the error happened, if known
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ExecError::At { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// Drops the location, used when it refers to text that is not the input, such as an alias body
+    pub fn without_span(self) -> ExecError {
+        match self {
+            ExecError::At { error, .. } => *error,
+            error => error,
+        }
+    }
+
+    /// The underlying error without locations and file information
+    pub fn root(&self) -> &ExecError {
+        match self {
+            ExecError::At { error, .. } | ExecError::InFile { error, .. } => error.root(),
+            error => error,
+        }
+    }
+}
+
+impl std::error::Error for ExecError {}
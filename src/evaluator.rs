@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::ast::{Argument, ArgumentKind, CommandInvocation, Comparison, Condition, Script, Statement};
+use crate::cbuf::Frame;
+use crate::error::ExecError;
+use crate::interpreter::Interpreter;
+use crate::output::OutputSink;
+use crate::token::{Span, StringPart};
+
+static ALIAS_MAX_CALLS: usize = 50000;
+
+/// Runs parsed scripts against an interpreter
+pub struct Evaluator<'a> {
+    interpreter: &'a mut Interpreter,
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(interpreter: &'a mut Interpreter) -> Self {
+        Evaluator { interpreter }
+    }
+
+    /// Runs every statement and stops at the first error. A `wait` hands the<br>
+    /// statements after it to the command buffer
+    pub fn run(&mut self, script: &Rc<Script>) -> Result<(), ExecError> {
+        self.resume(vec![Frame::new(script.clone(), None)])
+    }
+
+    /// Runs what is left of a script. Aliases and `if` blocks are pushed as frames instead<br>
+    /// of recursing so that an alias calling itself ends with an error rather than a stack overflow
+    pub(crate) fn resume(&mut self, mut frames: Vec<Frame>) -> Result<(), ExecError> {
+        // a wait asked for outside of a script stops nothing
+        self.interpreter.cbuf.take_wait();
+        let mut alias_calls = 0;
+
+        while let Some(frame) = frames.last_mut() {
+            let script = frame.script.clone();
+            let Some(statement) = script.statements.get(frame.next) else {
+                frames.pop();
+                continue;
+            };
+            frame.next += 1;
+            let call_span = frame.call_span;
+            let file = frame.file.clone();
+
+            let result = match statement {
+                Statement::Command(invocation) => match self.interpreter.commands_handler.variables.get(&invocation.name).cloned() {
+                    Some(body) => {
+                        let call_span = call_span.unwrap_or(invocation.name_span);
+                        self.push_alias(&mut frames, &body, call_span, &mut alias_calls).map_err(|err| (err, Some(call_span)))
+                    }
+                    None => self.invoke(invocation)
+                        .and_then(|()| self.push_inserted(&mut frames, invocation.span))
+                        .map_err(|err| (err, call_span)),
+                },
+                Statement::If(statement) => match self.condition(&statement.condition) {
+                    Ok(holds) => {
+                        let block = if holds { Some(&statement.then) } else { statement.otherwise.as_ref() };
+                        frames.extend(block.map(|block| Frame::new(block.clone(), call_span).in_file(file)));
+                        Ok(())
+                    }
+                    Err(err) => Err((err, call_span)),
+                },
+            };
+
+            if let Err((err, call_span)) = result {
+                // locations inside an alias body mean nothing to the caller, so point at the alias name
+                let err = match call_span {
+                    Some(span) => err.without_span().at(span),
+                    None => err,
+                };
+
+                // a statement of a config file failing stops only that statement, aliases
+                // it called included, and the error is reported with the file and line
+                let Some(index) = frames.iter().rposition(|frame| frame.file.is_some()) else {
+                    return Err(err);
+                };
+                frames.truncate(index + 1);
+                alias_calls = 0;
+
+                let err = frames[index].file.as_ref().unwrap().error(err, call_span.unwrap_or(statement.span()));
+                self.interpreter.output.error(&format!("{}\n", err));
+            }
+
+            if let Some(ticks) = self.interpreter.cbuf.take_wait().filter(|ticks| *ticks > 0) {
+                self.interpreter.cbuf.suspend(frames, ticks);
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_alias(&mut self, frames: &mut Vec<Frame>, body: &str, call_span: Span, alias_calls: &mut usize) -> Result<(), ExecError> {
+        *alias_calls += 1;
+        if *alias_calls > ALIAS_MAX_CALLS {
+            return Err(ExecError::AliasRecursionLimit(ALIAS_MAX_CALLS));
+        }
+
+        let script = Rc::new(self.interpreter.parse(body)?);
+
+        // frames with nothing left to run are dropped so that an alias ending by
+        // calling itself after a wait can loop forever
+        while frames.len() > 1 && frames.last().is_some_and(|frame| frame.next == frame.script.statements.len()) {
+            frames.pop();
+        }
+
+        frames.push(Frame::new(script, Some(call_span)));
+        Ok(())
+    }
+
+    /// Pushes what the statement that just ran asked to run before the rest of the script,<br>
+    /// e.g. the file of `exec`. A file that is already running is not run again
+    fn push_inserted(&mut self, frames: &mut Vec<Frame>, span: Span) -> Result<(), ExecError> {
+        let Some(frame) = self.interpreter.cbuf.take_inserted() else {
+            return Ok(());
+        };
+
+        if let Some(file) = &frame.file {
+            if frames.iter().any(|running| running.file.as_ref().is_some_and(|running| running.path == file.path)) {
+                return Err(ExecError::ExecCycle(file.name.clone()).at(span));
+            }
+        }
+
+        frames.push(frame);
+        Ok(())
+    }
+
+    /// Tells if a condition holds, comparing numbers when both sides are numbers
+    fn condition(&self, condition: &Condition) -> Result<bool, ExecError> {
+        let left = self.argument(&condition.left)?;
+
+        let Some((comparison, right)) = &condition.comparison else {
+            return Ok(!(left.is_empty() || left == "false" || left.parse::<f64>().is_ok_and(|number| number == 0.0)));
+        };
+        let right = self.argument(right)?;
+
+        let ordering = match (left.parse::<f64>(), right.parse::<f64>()) {
+            (Ok(left), Ok(right)) => left.partial_cmp(&right),
+            _ => Some(left.cmp(&right)),
+        };
+
+        Ok(match comparison {
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        })
+    }
+
+    /// Runs a statement naming a command or cvar
+    fn invoke(&mut self, invocation: &CommandInvocation) -> Result<(), ExecError> {
+        if self.interpreter.commands_handler.get(&invocation.name).is_some() {
+            self.call_command(invocation)
+        } else if self.interpreter.commands_handler.cvars.contains_key(&invocation.name) {
+            self.set_cvar(invocation)
+        } else {
+            Err(ExecError::UnknownCommand(invocation.name.clone()).at(invocation.name_span))
+        }
+    }
+
+    fn call_command(&mut self, invocation: &CommandInvocation) -> Result<(), ExecError> {
+        let (command, func) = self.interpreter.commands_handler.get_shared(&invocation.name).unwrap();
+        let command_span = invocation.name_span;
+
+        let args = &invocation.args;
+        let arguments = if command.rest_of_line && command.max_args > 0 && args.len() > command.max_args as usize {
+            // the words past the last argument belong to it. They are kept as they were written
+            // so that quotes and variables mean the same when the text is parsed again
+            let (args, rest) = args.split_at(command.max_args as usize - 1);
+            let mut arguments = self.arguments(args)?;
+
+            let text: Vec<&str> = rest.iter().map(|arg| arg.written.as_str()).collect();
+            arguments.push((text.join(" "), rest[0].span.to(rest[rest.len() - 1].span)));
+            arguments
+        } else {
+            self.arguments(args)?
+        };
+
+        let statement_span = arguments.last().map_or(command_span, |(_, span)| command_span.to(*span));
+
+        // checks if arguments size is within the allowed
+        if arguments.len() > command.max_args as usize || arguments.len() < command.min_args as usize {
+            return Err(ExecError::BadArity {
+                command: command.name.clone(),
+                usage: command.usage.clone(),
+                min: command.min_args,
+                max: command.max_args,
+                given: arguments.len(),
+            }.at(statement_span));
+        }
+
+        let values: Vec<String> = arguments.iter().map(|(value, _)| value.clone()).collect();
+        let result = command.convert_args(&values, &self.interpreter.commands_handler)
+            .and_then(|args| func.call(self.interpreter, &command, &args));
+
+        result.map_err(|err| {
+            // point at the argument the error is about when there is one
+            let offending = match &err {
+                ExecError::InvalidArgument { argument, .. } => Some(argument),
+                ExecError::UnknownCommand(name) | ExecError::UnknownVariable(name) => Some(name),
+                _ => None,
+            };
+
+            let span = offending
+                .and_then(|offending| arguments.iter().find(|(value, _)| value == offending))
+                .map_or(statement_span, |(_, span)| *span);
+            err.at(span)
+        })
+    }
+
+    /// `name` prints the cvar and `name value` sets it
+    fn set_cvar(&mut self, invocation: &CommandInvocation) -> Result<(), ExecError> {
+        let name = &invocation.name;
+
+        let arguments = self.arguments(&invocation.args)?;
+        if arguments.is_empty() {
+            let description = self.interpreter.commands_handler.cvars[name].describe();
+            self.interpreter.output.println(&description);
+            return Ok(());
+        }
+
+        let value: Vec<&str> = arguments.iter().map(|(value, _)| value.as_str()).collect();
+        let span = arguments[0].1.to(arguments[arguments.len() - 1].1);
+        self.interpreter.commands_handler.set_cvar_from_console(name, &value.join(" ")).map_err(|err| err.at(span))
+    }
+
+    /// Expands the variables in the arguments, keeping where each one is
+    fn arguments(&self, args: &[Argument]) -> Result<Vec<(String, Span)>, ExecError> {
+        args.iter().map(|arg| self.argument(arg).map(|value| (value, arg.span))).collect()
+    }
+
+    fn argument(&self, arg: &Argument) -> Result<String, ExecError> {
+        let value = match &arg.kind {
+            ArgumentKind::Literal(text) => Ok(text.clone()),
+            ArgumentKind::Variable(name) => self.interpreter.expand_variable(name, &format!("${name}")),
+            ArgumentKind::Interpolated(parts) => self.interpolate(parts),
+        };
+
+        value.map_err(|err| err.at(arg.span))
+    }
+
+    /// Builds the text of an interpolated string with its variables expanded
+    fn interpolate(&self, parts: &[StringPart]) -> Result<String, ExecError> {
+        let mut value = String::new();
+
+        for part in parts {
+            match part {
+                StringPart::Text(text) => value.push_str(text),
+                StringPart::Variable { name, written } => value.push_str(&self.interpreter.expand_variable(name, written)?),
+            }
+        }
+
+        Ok(value)
+    }
+}
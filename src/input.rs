@@ -0,0 +1,29 @@
+use crate::args::Args;
+
+/// A key going down or up. `+` commands run by a key get it as two implicit<br>
+/// arguments, `<key code> <timestamp>`, and so do their `-` counterparts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyEvent {
+    pub key_code: u32,
+    /// Seconds, in whatever clock the host uses
+    pub timestamp: f64,
+}
+
+impl KeyEvent {
+    pub fn new(key_code: u32, timestamp: f64) -> Self {
+        KeyEvent { key_code, timestamp }
+    }
+
+    /// Reads the implicit arguments back. `None` when the command was typed<br>
+    /// in the console instead of being run by a key
+    pub fn from_args(args: &Args) -> Option<KeyEvent> {
+        let key_code = args.get(0)?.as_f64()?;
+        let timestamp = args.get(1)?.as_f64()?;
+
+        Some(KeyEvent::new(key_code as u32, timestamp))
+    }
+
+    pub(crate) fn to_args(self) -> [String; 2] {
+        [self.key_code.to_string(), self.timestamp.to_string()]
+    }
+}
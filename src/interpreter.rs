@@ -0,0 +1,399 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::args::{Args, Param, ParamType};
+use crate::ast::{Argument, ArgumentKind, CommandInvocation, Script, Statement};
+use crate::bindings::Key;
+use crate::cbuf::{Cbuf, ExecFile, Frame};
+use crate::command::{self, Command, CommandDefinition, CommandsHandler};
+use crate::config;
+use crate::context::Context;
+use crate::error::ExecError;
+use crate::evaluator::Evaluator;
+use crate::input::KeyEvent;
+use crate::lexer::{Lexer, LexerOptions};
+use crate::output::{Output, OutputSink, StdoutSink};
+use crate::parser::Parser;
+use crate::timer::Timers;
+use crate::token::Span;
+
+/// What happens to `$name` when there is no alias or cvar called `name`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownVariablePolicy {
+    /// Leaves the text as it was written
+    #[default]
+    Keep,
+    /// Replaces it with nothing
+    Empty,
+    /// Fails with `ExecError::UnknownVariable`
+    Error,
+}
+
+/// Owns everything needed to run commands so that variables and<br>
+/// registrations are kept between `execute` calls
+pub struct Interpreter {
+    pub commands_handler: CommandsHandler,
+    pub output: Output,
+    /// Application state commands can reach, see `Context`
+    pub context: Context,
+    /// Directories searched, in order, for files given to `exec`
+    pub exec_paths: Vec<PathBuf>,
+    /// Optional comment styles understood when lexing input
+    pub lexer_options: LexerOptions,
+    /// How `$name` references to missing variables are expanded
+    pub unknown_variables: UnknownVariablePolicy,
+    /// Scripts stopped by `wait`, resumed by `tick`
+    pub cbuf: Cbuf,
+    /// Scripts set to run later by `delay` and `repeat`, also resumed by `tick`
+    pub timers: Timers,
+}
+
+impl Interpreter {
+    /// Creates an interpreter with the base commands already registered<br>
+    /// that prints to the standard output
+    pub fn new() -> Self {
+        Self::with_output(StdoutSink)
+    }
+
+    /// Same as `new` but everything printed goes to `output`
+    pub fn with_output(output: impl OutputSink + 'static) -> Self {
+        let mut commands_handler = CommandsHandler::new();
+        command::init_base_commands(&mut commands_handler);
+
+        let output = Output::new(output);
+
+        // keeps the output filter in sync with the developer cvar
+        let developer = output.developer_level();
+        commands_handler.get_cvar_mut("developer").unwrap().add_callback(move |_old, new| {
+            developer.set(new.as_f64().unwrap_or(0.0) as u8);
+            Ok(())
+        });
+
+        Interpreter {
+            commands_handler,
+            output,
+            context: Context::new(),
+            exec_paths: vec![PathBuf::from(".")],
+            lexer_options: LexerOptions::default(),
+            unknown_variables: UnknownVariablePolicy::default(),
+            cbuf: Cbuf::new(),
+            timers: Timers::new(),
+        }
+    }
+
+    /// Replaces where the output goes, keeping the developer level
+    pub fn set_output(&mut self, output: impl OutputSink + 'static) {
+        self.output.set_sink(output);
+    }
+
+    /// Parses and runs a line of input right away. Nothing runs if it does not parse,<br>
+    /// otherwise it stops at the first error. What follows a `wait` goes to<br>
+    /// the command buffer and runs in a later `tick`, use `enqueue` to run all of it there
+    pub fn execute(&mut self, line: &str) -> Result<(), ExecError> {
+        let script = Rc::new(self.parse(line)?);
+        self.run(&script)
+    }
+
+    /// Parses input without running it, so it can be checked, kept and run later
+    pub fn parse(&self, input: &str) -> Result<Script, ExecError> {
+        let lexer = Lexer::with_options(input.to_string(), self.lexer_options);
+        Parser::new(lexer, &self.commands_handler).parse()
+    }
+
+    /// Runs a parsed script, stopping at the first error. It is shared rather<br>
+    /// than copied, so a script kept around costs nothing to run again
+    pub fn run(&mut self, script: &Rc<Script>) -> Result<(), ExecError> {
+        Evaluator::new(self).run(script)
+    }
+
+    /// Parses `text` and adds it to the command buffer to run in the next `tick`,<br>
+    /// after what was already waiting for it. Nothing is added if it does not parse
+    pub fn enqueue(&mut self, text: &str) -> Result<(), ExecError> {
+        let script = Rc::new(self.parse(text)?);
+        self.cbuf.suspend(vec![Frame::new(script, None)], 1);
+        Ok(())
+    }
+
+    /// Advances the command buffer by one frame, running the scripts whose<br>
+    /// `wait` is over and then the timers that are due by the clock of `timers`.<br>
+    /// All of them run even if one fails and the first error is returned
+    pub fn tick(&mut self) -> Result<(), ExecError> {
+        let mut result = Ok(());
+
+        let resumed = self.cbuf.advance();
+        let due = self.timers.take_due().into_iter().map(|script| vec![Frame::new(script, None)]);
+        for frames in resumed.into_iter().chain(due) {
+            let ran = Evaluator::new(self).resume(frames);
+            if result.is_ok() {
+                result = ran;
+            }
+        }
+
+        result
+    }
+
+    /// Checks that every statement of `script`, including the ones in `if` blocks,<br>
+    /// names an alias, command or cvar
+    pub fn validate(&self, script: &Script) -> Result<(), ExecError> {
+        for statement in &script.statements {
+            match statement {
+                Statement::Command(invocation) => {
+                    let name = &invocation.name;
+                    if !self.commands_handler.variables.contains_key(name) && self.commands_handler.get(name).is_none() && !self.commands_handler.cvars.contains_key(name) {
+                        return Err(ExecError::UnknownCommand(name.clone()).at(invocation.name_span));
+                    }
+                }
+                Statement::If(statement) => {
+                    self.validate(&statement.then)?;
+                    if let Some(otherwise) = &statement.otherwise {
+                        self.validate(otherwise)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Value of `$name`: an alias body, or else the value of a cvar
+    pub fn variable_value(&self, name: &str) -> Option<String> {
+        if let Some(value) = self.commands_handler.variables.get(name) {
+            return Some(value.clone());
+        }
+
+        self.commands_handler.get_cvar(name).map(|cvar| cvar.value().to_string())
+    }
+
+    /// Expands a variable reference following `unknown_variables` when it does<br>
+    /// not exist. `written` is the reference as it appeared in the input
+    pub(crate) fn expand_variable(&self, name: &str, written: &str) -> Result<String, ExecError> {
+        match self.variable_value(name) {
+            Some(value) => Ok(value),
+            None => match self.unknown_variables {
+                UnknownVariablePolicy::Keep => Ok(written.to_string()),
+                UnknownVariablePolicy::Empty => Ok(String::new()),
+                UnknownVariablePolicy::Error => Err(ExecError::UnknownVariable(name.to_string())),
+            },
+        }
+    }
+
+    /// Registers a command taking any number of string arguments, e.g.<br>
+    /// `interpreter.register("hello", |interpreter, _args| { interpreter.output.println("hi"); Ok(()) })`.<br>
+    /// Returns false if the name is already taken by a command or cvar, or is a keyword
+    pub fn register<F>(&mut self, name: &str, func: F) -> bool
+    where
+        F: Fn(&mut Interpreter, &Args) -> Result<(), ExecError> + 'static,
+    {
+        self.register_command(Command::new(name, 0, u8::MAX, ""), func)
+    }
+
+    /// Same as `register` for a command with its parameters and usage already set.<br>
+    /// `CommandCall` types go through `CommandsHandler::register`
+    pub fn register_command<F>(&mut self, command: Command, func: F) -> bool
+    where
+        F: Fn(&mut Interpreter, &Args) -> Result<(), ExecError> + 'static,
+    {
+        self.commands_handler.register(command, func)
+    }
+
+    /// Registers `+name` and `-name`, run when a key bound to `+name` goes down<br>
+    /// and up. They get the key event, or `None` when typed in the console
+    pub fn register_button<P, R>(&mut self, name: &str, press: P, release: R) -> bool
+    where
+        P: Fn(&mut Interpreter, Option<KeyEvent>) -> Result<(), ExecError> + 'static,
+        R: Fn(&mut Interpreter, Option<KeyEvent>) -> Result<(), ExecError> + 'static,
+    {
+        let params = || vec![Param::new("key", ParamType::Int).optional(), Param::new("time", ParamType::Float).optional()];
+
+        let press_name = format!("+{}", name);
+        let release_name = format!("-{}", name);
+        if self.commands_handler.get(&release_name).is_some() || self.commands_handler.get_cvar(&release_name).is_some() {
+            return false;
+        }
+
+        self.register_command(Command::with_params(&press_name, params(), "starts holding"), move |interpreter, args| press(interpreter, KeyEvent::from_args(args)))
+            && self.register_command(Command::with_params(&release_name, params(), "stops holding"), move |interpreter, args| release(interpreter, KeyEvent::from_args(args)))
+    }
+
+    /// Runs `command` for a key going down. Statements naming a `+` command or<br>
+    /// alias, including the ones in `if` blocks, get the key code and timestamp as extra arguments
+    pub fn press_command(&mut self, command: &str, event: KeyEvent) -> Result<(), ExecError> {
+        let mut script = self.parse(command)?;
+        add_key_arguments(&mut script, event);
+        self.run(&Rc::new(script))
+    }
+
+    /// Runs the `-` counterpart of every `+` statement of `command` for a key going up.<br>
+    /// Both blocks of an `if` count, its condition may have changed since the key went down<br>
+    /// and nothing should stay held
+    pub fn release_command(&mut self, command: &str, event: KeyEvent) -> Result<(), ExecError> {
+        let script = self.parse(command)?;
+
+        let mut statements = vec!();
+        self.release_statements(&script, event, &mut statements);
+
+        self.run(&Rc::new(Script { statements }))
+    }
+
+    fn release_statements(&self, script: &Script, event: KeyEvent, statements: &mut Vec<Statement>) {
+        for statement in &script.statements {
+            match statement {
+                Statement::Command(invocation) => {
+                    if let Some(name) = self.commands_handler.release_of(&invocation.name) {
+                        statements.push(Statement::Command(CommandInvocation {
+                            name,
+                            args: key_arguments(event, invocation.name_span).collect(),
+                            ..invocation.clone()
+                        }));
+                    }
+                }
+                Statement::If(statement) => {
+                    self.release_statements(&statement.then, event, statements);
+                    if let Some(otherwise) = &statement.otherwise {
+                        self.release_statements(otherwise, event, statements);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs what `key` is bound to as it goes down. `timestamp` is passed to<br>
+    /// `+` commands along with the key code. Repeated presses of a held key are ignored
+    pub fn press_key(&mut self, key: Key, timestamp: f64) -> Result<(), ExecError> {
+        match self.commands_handler.bindings.press(key) {
+            Some(command) => self.press_command(&command, KeyEvent::new(key.code(), timestamp)),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the `-` counterparts of what `key` ran when it went down
+    pub fn release_key(&mut self, key: Key, timestamp: f64) -> Result<(), ExecError> {
+        match self.commands_handler.bindings.release(key) {
+            Some(command) => self.release_command(&command, KeyEvent::new(key.code(), timestamp)),
+            None => Ok(()),
+        }
+    }
+
+    /// Registers a command defined with `#[haybcmd::command]`
+    pub fn register_definition(&mut self, definition: impl CommandDefinition + 'static) -> bool {
+        let command = definition.command();
+        self.commands_handler.register(command, definition)
+    }
+
+    /// Calls the function registered for `name` with already validated arguments
+    pub fn call_command(&mut self, name: &str, args: &Args) -> Result<(), ExecError> {
+        let (command, func) = self.commands_handler.get_shared(name).ok_or_else(|| ExecError::UnknownCommand(name.to_string()))?;
+        func.call(self, &command, args)?;
+
+        // there is no script to run a file given to exec before
+        match self.cbuf.take_inserted() {
+            Some(frame) => Evaluator::new(self).resume(vec![frame]),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs a config file as a single script, so a `wait` holds back the rest of<br>
+    /// the file. Relative paths are looked up in `exec_paths` and `.cfg` is tried<br>
+    /// when the name has no extension. A statement that does not parse or fails is<br>
+    /// printed as an error with the file and line, then the file goes on with the next one.<br>
+    /// Only a file that can not be found or read is an error. The `exec` command runs<br>
+    /// the file as part of the script calling it instead, so a `wait` holds back that script too
+    pub fn exec_file(&mut self, file: &str) -> Result<(), ExecError> {
+        let frame = self.load_file(file)?;
+        Evaluator::new(self).resume(vec![frame])
+    }
+
+    /// Reads and parses a config file, printing the statements that do not parse
+    pub(crate) fn load_file(&mut self, file: &str) -> Result<Frame, ExecError> {
+        let path = self.resolve_exec_path(file).ok_or_else(|| ExecError::Io {
+            path: file.to_string(),
+            reason: "file not found".to_string(),
+        })?;
+
+        let contents = fs::read_to_string(&path).map_err(|err| ExecError::Io {
+            path: path.display().to_string(),
+            reason: err.to_string(),
+        })?;
+
+        let lexer = Lexer::with_options(contents, self.lexer_options);
+        let (script, errors) = Parser::new(lexer, &self.commands_handler).parse_recovering();
+
+        let file = ExecFile {
+            name: path.display().to_string(),
+            path: fs::canonicalize(&path).unwrap_or(path),
+        };
+        for error in errors {
+            let error = file.error(error, Span::new(0, 0, 1, 1));
+            self.output.error(&format!("{}\n", error));
+        }
+
+        Ok(Frame::new(Rc::new(script), None).in_file(Some(Rc::new(file))))
+    }
+
+    /// Writes the aliases, bindings and archived cvars to `file` so that `exec`ing it<br>
+    /// restores them. Relative paths go into the first of `exec_paths`
+    pub fn write_config(&self, file: &str) -> Result<(), ExecError> {
+        let mut path = PathBuf::from(file);
+        if path.is_relative() {
+            if let Some(dir) = self.exec_paths.first() {
+                path = dir.join(path);
+            }
+        }
+
+        fs::write(&path, config::write_config(&self.commands_handler)).map_err(|err| ExecError::Io {
+            path: path.display().to_string(),
+            reason: err.to_string(),
+        })
+    }
+
+    fn resolve_exec_path(&self, file: &str) -> Option<PathBuf> {
+        let path = Path::new(file);
+
+        let mut candidates = vec![path.to_path_buf()];
+        if path.extension().is_none() {
+            candidates.push(path.with_extension("cfg"));
+        }
+
+        if path.is_absolute() {
+            return candidates.into_iter().find(|candidate| candidate.is_file());
+        }
+
+        self.exec_paths.iter()
+            .flat_map(|dir| candidates.iter().map(move |candidate| dir.join(candidate)))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+/// Adds the arguments of a key event to every `+` statement of `script`
+fn add_key_arguments(script: &mut Script, event: KeyEvent) {
+    for statement in &mut script.statements {
+        match statement {
+            Statement::Command(invocation) => {
+                if invocation.name.starts_with('+') {
+                    invocation.args.extend(key_arguments(event, invocation.name_span));
+                }
+            }
+            Statement::If(statement) => {
+                add_key_arguments(Rc::make_mut(&mut statement.then), event);
+                if let Some(otherwise) = &mut statement.otherwise {
+                    add_key_arguments(Rc::make_mut(otherwise), event);
+                }
+            }
+        }
+    }
+}
+
+/// The implicit arguments of a key event, pointing at the command they were added to
+fn key_arguments(event: KeyEvent, span: Span) -> impl Iterator<Item = Argument> {
+    event.to_args().into_iter().map(move |text| {
+        let kind = ArgumentKind::Literal(text);
+        Argument { written: kind.to_string(), kind, span }
+    })
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
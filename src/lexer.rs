@@ -1,46 +1,156 @@
-use crate::token::{Token, TokenType};
+use crate::token::{Span, StringPart, Token, TokenType};
 use crate::command;
+use crate::error::ExecError;
 
+/// Optional lexer features. `//` line comments are always recognized
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct LexerOptions {
+    /// `#` also starts a line comment
+    pub hash_comments: bool,
+    /// `/* ... */` block comments
+    pub block_comments: bool,
+}
+
+/// Splits the input into tokens. It walks the input once, keeping a byte<br>
+/// offset into it, so lexing is linear and works with any UTF-8 text
 #[derive(Clone)]
 pub struct Lexer {
     input: String,
-    position: usize,
+    offset: usize,
+    line: usize,
+    column: usize,
     last_token: Token,
+    options: LexerOptions,
 }
 
 impl Lexer {
     pub fn new(input: String) -> Self {
+        Self::with_options(input, LexerOptions::default())
+    }
+
+    pub fn with_options(input: String, options: LexerOptions) -> Self {
         Lexer {
             input,
-            position: 0,
-            last_token: Token::new(TokenType::NOTHING, "".to_string())
+            offset: 0,
+            line: 1,
+            column: 1,
+            last_token: Token::new(TokenType::NOTHING, "".to_string()),
+            options,
         }
     }
 
-    pub fn next_token(&mut self, commands_handler: &command::CommandsHandler) -> Token {
-        if self.position >= self.input.len() {
-            self.last_token = Token::new(TokenType::EOF, "".to_string());
-            return self.last_token.clone();
+    pub fn next_token(&mut self, commands_handler: &command::CommandsHandler) -> Result<Token, ExecError> {
+        loop {
+            while self.peek().is_some_and(|c| c.is_whitespace() && c != '\n') {
+                self.bump();
+            }
+
+            let start = self.span_start();
+            if !self.skip_comment().map_err(|err| err.at(self.span_from(start)))? {
+                break;
+            }
+        }
+
+        let start = self.span_start();
+
+        let mut token = match self.peek() {
+            None => Token::new(TokenType::EOF, "".to_string()),
+            Some(c @ (';' | '\n')) => {
+                self.bump();
+                Token::new(TokenType::EOS, c.to_string())
+            }
+            Some(_) => self.parse_token(commands_handler).map_err(|err| err.at(self.span_from(start)))?,
+        };
+
+        token.span = self.span_from(start);
+        self.last_token = token;
+        Ok(self.last_token.clone())
+    }
+
+    /// Goes on from the start of `line`, e.g. to skip what is left of a line with an error.<br>
+    /// Returns false if the input does not have that many lines
+    pub fn restart_at_line(&mut self, line: usize) -> bool {
+        let offset = match line {
+            0 => return false,
+            1 => 0,
+            _ => match self.input.match_indices('\n').nth(line - 2) {
+                Some((newline, _)) => newline + 1,
+                None => return false,
+            },
+        };
+
+        self.offset = offset;
+        self.line = line;
+        self.column = 1;
+        self.last_token = Token::new(TokenType::NOTHING, "".to_string());
+        true
+    }
+
+    /// The input text at `span`
+    pub fn text(&self, span: Span) -> &str {
+        &self.input[span.start..span.end]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn starts_with(&self, pattern: &str) -> bool {
+        self.input[self.offset..].starts_with(pattern)
+    }
+
+    /// Moves past the current character, keeping track of the line and column
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
 
-        let current_char = self.input.chars().nth(self.position).unwrap();
-        while current_char.is_whitespace() {
-            self.position += 1;
+        Some(c)
+    }
 
-            if self.position >= self.input.len() {
-                self.last_token = Token::new(TokenType::EOF, "".to_string());
-                return self.last_token.clone();
+    fn span_start(&self) -> Span {
+        Span::new(self.offset, self.offset, self.line, self.column)
+    }
+
+    fn span_from(&self, start: Span) -> Span {
+        Span {
+            end: self.offset,
+            ..start
+        }
+    }
+
+    /// Skips a comment starting at the current position and tells if there was one.<br>
+    /// Line comments stop before the newline
+    fn skip_comment(&mut self) -> Result<bool, ExecError> {
+        if self.starts_with("//") || (self.options.hash_comments && self.starts_with("#")) {
+            while self.peek().is_some_and(|c| c != '\n') {
+                self.bump();
             }
+            return Ok(true);
         }
 
-        if self.input.chars().nth(self.position).unwrap() == ';' {
-            self.position += 1;
-            self.last_token = Token::new(TokenType::EOS, ";".to_string());
-            return self.last_token.clone();
+        if self.options.block_comments && self.starts_with("/*") {
+            // Skip the opening /*
+            self.bump();
+            self.bump();
+            while !self.starts_with("*/") {
+                if self.bump().is_none() {
+                    return Err(ExecError::UnterminatedComment);
+                }
+            }
+            // Skip the closing */
+            self.bump();
+            self.bump();
+            return Ok(true);
         }
 
-        self.last_token = self.parse_token(commands_handler);
-        self.last_token.clone()
+        Ok(false)
     }
 
     fn is_variable(&self, identifier: &str) -> bool {
@@ -48,47 +158,174 @@ impl Lexer {
     }
 
     fn is_command(&self, command_name: &str, commands_handler: &command::CommandsHandler) -> bool {
-        commands_handler.commands.iter().any(|command| command.name == command_name)
+        commands_handler.get(command_name).is_some()
     }
 
-    fn parse_token(&mut self, commands_handler: &command::CommandsHandler) -> Token {
-        if self.input.chars().nth(self.position).unwrap() == '"' {
-            return self.parse_string();
+    fn parse_token(&mut self, commands_handler: &command::CommandsHandler) -> Result<Token, ExecError> {
+        match self.peek() {
+            Some('"') => return self.parse_string(),
+            Some('\'') => return self.parse_literal_string(),
+            _ => {}
         }
 
-        let mut token_value = String::new();
-        while self.position < self.input.len()
-            && !self.input.chars().nth(self.position).unwrap().is_whitespace()
-            && self.input.chars().nth(self.position).unwrap() != ';'
-        {
-            token_value.push(self.input.chars().nth(self.position).unwrap());
-            self.position += 1;
+        let start = self.offset;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && c != ';') {
+            self.bump();
         }
+        let token_value = self.input[start..self.offset].to_string();
 
-        if self.is_command(&token_value, commands_handler) && (self.last_token.token_type() == &TokenType::NOTHING || self.last_token.token_type() != &TokenType::COMMAND) {
-            Token::new(TokenType::COMMAND, token_value)
+        if token_value == "{" {
+            Ok(Token::new(TokenType::LBRACE, token_value))
+        } else if token_value == "}" {
+            Ok(Token::new(TokenType::RBRACE, token_value))
+        } else if self.is_command(&token_value, commands_handler) && (self.last_token.token_type() == &TokenType::NOTHING || self.last_token.token_type() != &TokenType::COMMAND) {
+            Ok(Token::new(TokenType::COMMAND, token_value))
         } else if self.is_variable(&token_value) {
-            Token::new(TokenType::VARIABLE, token_value)
+            Ok(Token::new(TokenType::VARIABLE, token_value))
         } else {
-            Token::new(TokenType::STRING, token_value)
+            Ok(Token::new(TokenType::STRING, token_value))
         }
     }
 
-    fn parse_string(&mut self) -> Token {
+    /// Double quoted string. Understands `\"`, `\\`, `\n`, `\t`, `\;`, `\$` and<br>
+    /// `\u{..}`; a backslash before anything else is kept as is.<br>
+    /// `$name` and `${name}` make it an `INTERPOLATED` token
+    fn parse_string(&mut self) -> Result<Token, ExecError> {
         let mut token_value = String::new();
+        let mut parts = vec!();
+        let mut text = String::new();
+
+        self.bump(); // Skip the first double quote
+        loop {
+            match self.bump() {
+                None => return Err(ExecError::UnterminatedString),
+                Some('"') => break,
+                Some('\\') => {
+                    let before = text.len();
+                    self.parse_escape(&mut text)?;
+                    token_value.push_str(&text[before..]);
+                }
+                Some('$') => match self.parse_variable_reference() {
+                    Some((name, written)) => {
+                        if !text.is_empty() {
+                            parts.push(StringPart::Text(std::mem::take(&mut text)));
+                        }
+                        token_value.push_str(&written);
+                        parts.push(StringPart::Variable { name, written });
+                    }
+                    None => {
+                        text.push('$');
+                        token_value.push('$');
+                    }
+                },
+                Some(c) => {
+                    text.push(c);
+                    token_value.push(c);
+                }
+            }
+        }
+
+        if parts.is_empty() {
+            return Ok(Token::new(TokenType::STRING, token_value));
+        }
+
+        if !text.is_empty() {
+            parts.push(StringPart::Text(text));
+        }
+
+        let mut token = Token::new(TokenType::INTERPOLATED, token_value);
+        token.parts = parts;
+        Ok(token)
+    }
+
+    /// Reads the name after a `$`, returning it with the text as it was written.<br>
+    /// `None` if the `$` is not followed by a name, in which case it is literal
+    fn parse_variable_reference(&mut self) -> Option<(String, String)> {
+        let rest = &self.input[self.offset..];
+
+        let (name, length) = if let Some(braced) = rest.strip_prefix('{') {
+            let end = braced.find(['}', '"'])?;
+            if !braced[end..].starts_with('}') || end == 0 {
+                return None;
+            }
+            (braced[..end].to_string(), end + 2)
+        } else {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            if end == 0 {
+                return None;
+            }
+            (rest[..end].to_string(), end)
+        };
+
+        let written = format!("${}", &rest[..length]);
+        let end = self.offset + length;
+        while self.offset < end {
+            self.bump();
+        }
+
+        Some((name, written))
+    }
 
-        self.position += 1; // Skip the first double quote
-        while self.position < self.input.len() && self.input.chars().nth(self.position).unwrap() != '"' {
-            if self.input.chars().nth(self.position).unwrap() == '\\' && self.position + 1 < self.input.len() && self.input.chars().nth(self.position + 1).unwrap() == '"' {
-                self.position += 1; // Skip the backslash
+    /// Reads what follows a backslash inside a double quoted string
+    fn parse_escape(&mut self, token_value: &mut String) -> Result<(), ExecError> {
+        match self.peek() {
+            Some(c @ ('"' | '\\' | ';' | '$')) => {
+                self.bump();
+                token_value.push(c);
             }
+            Some('n') => {
+                self.bump();
+                token_value.push('\n');
+            }
+            Some('t') => {
+                self.bump();
+                token_value.push('\t');
+            }
+            Some('u') => {
+                self.bump();
+                token_value.push(self.parse_unicode_escape()?);
+            }
+            _ => token_value.push('\\'),
+        }
 
-            token_value.push(self.input.chars().nth(self.position).unwrap());
-            self.position += 1;
+        Ok(())
+    }
+
+    /// Reads the `{..}` part of a `\u{..}` escape
+    fn parse_unicode_escape(&mut self) -> Result<char, ExecError> {
+        if self.bump() != Some('{') {
+            return Err(ExecError::InvalidEscape("expected { after \\u".to_string()));
+        }
+
+        let start = self.offset;
+        while self.peek().is_some_and(|c| c != '}' && c != '"') {
+            self.bump();
         }
+        let digits = self.input[start..self.offset].to_string();
 
-        self.position += 1; // Skip the last double quote
+        if self.bump() != Some('}') {
+            return Err(ExecError::InvalidEscape(format!("\\u{{{} is missing the closing }}", digits)));
+        }
 
-        Token::new(TokenType::STRING, token_value)
+        u32::from_str_radix(&digits, 16).ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| ExecError::InvalidEscape(format!("\\u{{{}}} is not a valid unicode character", digits)))
     }
-}
\ No newline at end of file
+
+    /// Single quoted string, taken literally without escapes
+    fn parse_literal_string(&mut self) -> Result<Token, ExecError> {
+        self.bump(); // Skip the first single quote
+
+        let start = self.offset;
+        while self.peek().is_some_and(|c| c != '\'') {
+            self.bump();
+        }
+        let token_value = self.input[start..self.offset].to_string();
+
+        if self.bump().is_none() {
+            return Err(ExecError::UnterminatedString);
+        }
+
+        Ok(Token::new(TokenType::STRING, token_value))
+    }
+}
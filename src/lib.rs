@@ -1,23 +1,23 @@
-use command::CommandsFuncs;
-
+pub mod error;
 pub mod output;
+pub mod value;
+pub mod cvar;
 pub mod token;
 pub mod lexer;
+pub mod ast;
 pub mod parser;
+pub mod evaluator;
+pub mod cbuf;
+pub mod timer;
+pub mod args;
+pub mod bindings;
 pub mod command;
+pub mod interpreter;
+pub mod config;
+pub mod context;
+pub mod input;
 
-pub fn init() -> (command::CommandsHandler, command::CommandsFuncs) {
-    let mut commands_handler = command::CommandsHandler::new();
-    let mut commands_funcs = command::CommandsFuncs::new();
-
-    command::init_base_commands(&mut commands_handler, &mut commands_funcs);
-
-    (commands_handler, commands_funcs)
-}
-
-pub fn parse(commands_handler: command::CommandsHandler, commands_funcs: CommandsFuncs, str: String) {
-    let lexer = lexer::Lexer::new(str);
-
-    let mut parser_var = parser::Parser::new(Box::new(lexer), Box::new(commands_handler), commands_funcs);
-    parser_var.parse();
-}
\ No newline at end of file
+pub use error::ExecError;
+pub use haybcmd_macros::command;
+pub use interpreter::{Interpreter, UnknownVariablePolicy};
+pub use value::{Value, ValueType};
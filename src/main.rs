@@ -1,15 +1,43 @@
 use std::io;
 
+use haybcmd::output::{AnsiSink, OutputSink};
+use haybcmd::token::Span;
+
+/// Repeats the input with the part an error points at underlined
+fn underline(line: &str, span: Span) -> String {
+    let padding = line.get(..span.start).map_or(0, |prefix| prefix.chars().count());
+    let width = line.get(span.start..span.end).map_or(1, |text| text.chars().count()).max(1);
+
+    format!("{}\n{}{}\n", line, " ".repeat(padding), "^".repeat(width))
+}
+
 fn main() {
-    let (commands_handler, commands_funcs) = haybcmd::init();
+    let mut interpreter = haybcmd::Interpreter::with_output(AnsiSink);
 
-    //haybcmd::add_cvar("tf_fuck", tf_fuck, "1 or 0"); // - type: boolean - 1/0 is a default description for bool cvars(\n the rest of the description)
+    // config files given as arguments run before reading the input
+    for file in std::env::args().skip(1) {
+        if let Err(err) = interpreter.exec_file(&file) {
+            interpreter.output.error(&format!("{}\n", err));
+        }
+    }
 
     loop {
         let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read line");
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            break;
+        }
 
-        haybcmd::parse(commands_handler.clone(), commands_funcs, input.trim().to_string());
-    }
+        let line = input.trim();
+        if let Err(err) = interpreter.execute(line) {
+            if let Some(span) = err.span() {
+                interpreter.output.print(&underline(line, span));
+            }
+            interpreter.output.error(&format!("{}\n", err));
+        }
 
-}
\ No newline at end of file
+        // every line read is a frame for the scripts that are waiting
+        if let Err(err) = interpreter.tick() {
+            interpreter.output.error(&format!("{}\n", err));
+        }
+    }
+}
@@ -1,18 +1,256 @@
-/// while it's just testing, it will be as simple as that,<br>
-/// but when i start making a game i will change this code<br>
-/// to be related with the game interface
-pub fn print<S: ToString>(s: S) { 
-    print!("{}", s.to_string());
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::rc::Rc;
+
+/// How important a message is, like Source's `Msg`/`Warning`/`DevMsg`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+    /// Only shown when the `developer` level is at least this value
+    Developer(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+}
+
+/// A piece of text together with how it should be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Message<'a> {
+    pub severity: Severity,
+    pub color: Option<Color>,
+    pub text: &'a str,
+}
+
+impl<'a> Message<'a> {
+    pub fn new(severity: Severity, text: &'a str) -> Self {
+        Message {
+            severity,
+            color: None,
+            text,
+        }
+    }
+}
+
+/// Destination for everything commands print.<br>
+/// The interpreter owns one and hands it to every command call
+pub trait OutputSink {
+    fn write(&mut self, message: &Message);
+
+    fn print(&mut self, text: &str) {
+        self.write(&Message::new(Severity::Info, text));
+    }
+
+    fn println(&mut self, text: &str) {
+        self.print(&format!("{}\n", text));
+    }
+
+    fn warning(&mut self, text: &str) {
+        self.write(&Message::new(Severity::Warning, text));
+    }
+
+    fn error(&mut self, text: &str) {
+        self.write(&Message::new(Severity::Error, text));
+    }
+
+    fn dev_msg(&mut self, level: u8, text: &str) {
+        self.write(&Message::new(Severity::Developer(level), text));
+    }
+
+    fn color_print(&mut self, color: Color, text: &str) {
+        self.write(&Message {
+            severity: Severity::Info,
+            color: Some(color),
+            text,
+        });
+    }
+
+    fn print_command_usage(&mut self, name: &str, usage: &str) {
+        self.println(&format!("{} {}", name, usage));
+    }
 }
 
-pub fn println<S: ToString>(s: S) { 
-    println!("{}", s.to_string());
+/// The sink the interpreter hands to commands. Drops developer messages<br>
+/// above the `developer` level before forwarding to the real sink
+pub struct Output {
+    sink: Box<dyn OutputSink>,
+    developer: Rc<Cell<u8>>,
 }
 
-pub fn print_unknown_command(command: &str) {
-    println!("unknown command \"{}\"", command);
+impl Output {
+    pub fn new(sink: impl OutputSink + 'static) -> Self {
+        Output {
+            sink: Box::new(sink),
+            developer: Rc::new(Cell::new(0)),
+        }
+    }
+
+    pub fn developer(&self) -> u8 {
+        self.developer.get()
+    }
+
+    /// Shared handle to the developer level, so it can be updated from<br>
+    /// outside, e.g. by the `developer` cvar callback
+    pub fn developer_level(&self) -> Rc<Cell<u8>> {
+        self.developer.clone()
+    }
+
+    pub fn set_sink(&mut self, sink: impl OutputSink + 'static) {
+        self.sink = Box::new(sink);
+    }
+
+    pub fn is_shown(&self, severity: Severity) -> bool {
+        match severity {
+            Severity::Developer(level) => level <= self.developer.get(),
+            _ => true,
+        }
+    }
+}
+
+impl OutputSink for Output {
+    fn write(&mut self, message: &Message) {
+        if self.is_shown(message.severity) {
+            self.sink.write(message);
+        }
+    }
+}
+
+/// Prints to the process standard output as plain text
+#[derive(Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write(&mut self, message: &Message) {
+        print!("{}", message.text);
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Prints to the standard output using ANSI escape codes for colors.<br>
+/// Warnings are yellow, errors red and developer messages dimmed unless<br>
+/// the message has its own color
+#[derive(Default)]
+pub struct AnsiSink;
+
+impl AnsiSink {
+    fn color_of(message: &Message) -> Option<Color> {
+        message.color.or(match message.severity {
+            Severity::Info => None,
+            Severity::Warning => Some(Color::new(255, 200, 0)),
+            Severity::Error => Some(Color::new(255, 64, 64)),
+            Severity::Developer(_) => Some(Color::new(150, 150, 150)),
+        })
+    }
 }
 
-pub fn print_command_usage(name: String, usage: String) {
-    println(&format!("{} {}", name, usage));
-}
\ No newline at end of file
+impl OutputSink for AnsiSink {
+    fn write(&mut self, message: &Message) {
+        match Self::color_of(message) {
+            Some(color) => print!("\x1b[38;2;{};{};{}m{}\x1b[0m", color.r, color.g, color.b, message.text),
+            None => print!("{}", message.text),
+        }
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Keeps the text in memory. Clones share the same buffer, so a clone<br>
+/// can be kept around to read what the interpreter printed
+#[derive(Clone, Default)]
+pub struct BufferSink {
+    buffer: Rc<RefCell<String>>,
+}
+
+impl BufferSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contents(&self) -> String {
+        self.buffer.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.buffer.borrow_mut().clear();
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn write(&mut self, message: &Message) {
+        self.buffer.borrow_mut().push_str(message.text);
+    }
+}
+
+/// Forwards every message to a closure
+pub struct CallbackSink {
+    callback: Box<dyn FnMut(&Message)>,
+}
+
+impl CallbackSink {
+    pub fn new(callback: impl FnMut(&Message) + 'static) -> Self {
+        CallbackSink {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl OutputSink for CallbackSink {
+    fn write(&mut self, message: &Message) {
+        (self.callback)(message);
+    }
+}
+
+/// Writes the text to anything implementing `io::Write`, such as a log file
+pub struct WriterSink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> WriterSink<W> {
+    pub fn new(writer: W) -> Self {
+        WriterSink { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> OutputSink for WriterSink<W> {
+    fn write(&mut self, message: &Message) {
+        let _ = self.writer.write_all(message.text.as_bytes());
+    }
+}
+
+/// Mirrors the output to several sinks, e.g. the console and a log file
+#[derive(Default)]
+pub struct TeeSink {
+    sinks: Vec<Box<dyn OutputSink>>,
+}
+
+impl TeeSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, sink: impl OutputSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+}
+
+impl OutputSink for TeeSink {
+    fn write(&mut self, message: &Message) {
+        for sink in self.sinks.iter_mut() {
+            sink.write(message);
+        }
+    }
+}
@@ -1,167 +1,249 @@
-use crate::command::{CommandsFuncs, CommandsHandler};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::ast::{Argument, ArgumentKind, CommandInvocation, Comparison, Condition, IfStatement, Script, Statement};
+use crate::command::CommandsHandler;
+use crate::error::ExecError;
 use crate::lexer::Lexer;
-use crate::output;
-use crate::token::{Token, TokenType};
-
-pub struct Parser {
-    lexer: Box<Lexer>,
-    current_token: Token,
-    commands_handler: Box<CommandsHandler>,
-    commands_funcs: CommandsFuncs
+use crate::token::{Span, Token, TokenType};
+
+/// Words starting or continuing an `if`, quoted or not, so nothing can be named after them
+pub const KEYWORDS: [&str; 2] = ["if", "else"];
+
+/// Turns tokens into a `Script` without running anything
+pub struct Parser<'a> {
+    lexer: Lexer,
+    commands_handler: &'a CommandsHandler,
+    /// Tokens read ahead by `peek` or put back by `unread`
+    peeked: VecDeque<Token>,
+    /// Where the last token taken by `advance` is
+    last_span: Span,
 }
 
-static ALIAS_MAX_CALLS: usize = 50000;
-
-impl Parser{
-    pub fn new(lexer: Box<Lexer>, commands_handler: Box<CommandsHandler>, commands_funcs: CommandsFuncs) -> Self {
+impl<'a> Parser<'a> {
+    pub fn new(lexer: Lexer, commands_handler: &'a CommandsHandler) -> Self {
         Parser {
             lexer,
-            current_token: Token::new(TokenType::NOTHING, "".to_string()),
             commands_handler,
-            commands_funcs
+            peeked: VecDeque::new(),
+            last_span: Span::default(),
         }
     }
 
-    pub fn parse(&mut self) {
-        while self.current_token.token_type() != &TokenType::EOF {
-            let variable_value = self.get_variable_from_current_token_value();
-
-            if !variable_value.is_empty() {
-                self.handle_alias_lexer(&variable_value);
-            } else if self.current_token.token_type() == &TokenType::COMMAND {
-                self.handle_command_token();
-            } else if self.current_token.token_type() == &TokenType::STRING {
-                output::print_unknown_command(self.current_token.value());
-                self.advance_until(&[TokenType::EOS]);
-            }
-
-            self.advance();
-        }
+    /// Parses the whole input, stopping at the first lexing or syntax error
+    pub fn parse(&mut self) -> Result<Script, ExecError> {
+        self.parse_statements(false)
     }
 
-    fn get_variable_from_current_token_value(&self) -> String {
-        if let Some(value) = self.commands_handler.variables.get(self.current_token.value()) {
-            value.clone()
-        } else {
-            "".to_string()
+    /// Parses the whole input, skipping the rest of the line when a statement has an error<br>
+    /// so that the ones on the following lines are still parsed. Returns every error found
+    pub fn parse_recovering(&mut self) -> (Script, Vec<ExecError>) {
+        let mut statements = vec!();
+        let mut errors = vec!();
+
+        loop {
+            match self.parse_statement(false) {
+                Ok((statement, last_token)) => {
+                    statements.extend(statement);
+                    if last_token == TokenType::EOF {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    let line = error.span().map_or(self.last_span.line, |span| span.line);
+                    errors.push(error);
+
+                    self.peeked.clear();
+                    if !self.lexer.restart_at_line(line + 1) {
+                        break;
+                    }
+                }
+            }
         }
-    }
 
-    fn advance(&mut self) {
-        self.current_token = self.lexer.next_token(&self.commands_handler);
+        (Script { statements }, errors)
     }
 
-    fn advance_until(&mut self, token_types: &[TokenType]) {
-        self.advance(); // always skip the first one
+    fn advance(&mut self) -> Result<Token, ExecError> {
+        let token = match self.peeked.pop_front() {
+            Some(token) => token,
+            None => self.lexer.next_token(self.commands_handler)?,
+        };
 
-        // checks if EOF is reached because if not, it would run forever
-        while !token_types.contains(&self.current_token.token_type()) && self.current_token.token_type() != &TokenType::EOF {
-            self.advance();
-        }
+        self.last_span = token.span;
+        Ok(token)
     }
 
-    fn handle_command_token(&mut self) {
-        let command_opt = self.commands_handler.get(self.current_token.value(), true);
-
-        if command_opt.is_none() {
-            return;
+    fn peek(&mut self) -> Result<&Token, ExecError> {
+        if self.peeked.is_empty() {
+            let token = self.lexer.next_token(self.commands_handler)?;
+            self.peeked.push_back(token);
         }
 
-        let command = command_opt.unwrap();
+        Ok(&self.peeked[0])
+    }
+
+    /// Puts `token` back so it is the next one taken by `advance`
+    fn unread(&mut self, token: Token) {
+        self.peeked.push_front(token);
+    }
 
-        let command_name = command.name.clone();
-        let command_usage = command.usage.clone();
-        let command_max_args: u8 = command.max_args;
-        let command_min_args: u8 = command.min_args;
+    /// Statements until the end of the input, or until the `}` closing the block when `in_block`
+    fn parse_statements(&mut self, in_block: bool) -> Result<Script, ExecError> {
+        let mut statements = vec!();
 
-        self.advance(); // skips the command token
+        loop {
+            let (statement, last_token) = self.parse_statement(in_block)?;
+            statements.extend(statement);
 
-        let mut arguments = self.get_arguments();
-        
-        // make it include whitespaces in that case
-        if command_max_args == 1 && !arguments.is_empty() {
-            let mut str = String::new();
-            for arg in &arguments {
-                str.push_str(format!("{arg} ").as_str());
+            match last_token {
+                TokenType::EOF if in_block => return Err(ExecError::Syntax("expected } before the end of the input".to_string()).at(self.peek()?.span)),
+                TokenType::EOF => break,
+                TokenType::RBRACE => break,
+                _ => {}
             }
-            arguments.clear();
-            arguments.push(str.trim().to_string());
-        
-        } else {
-            arguments.clear();
         }
 
-        // checks if arguments size is within the allowed
-        if arguments.len() > command_max_args as usize || arguments.len() < command_min_args as usize {
-            output::print_command_usage(command_name, command_usage);
-            if !arguments.is_empty() {
-                output::print(&format!("arguments size must be within range [{}, {}], but size is {}\n", command_min_args, command_max_args, arguments.len()));
-            }
-            return;
-        }
-        
-        self.commands_funcs.call(&mut self.commands_handler, command, arguments);
+        Ok(Script { statements })
     }
 
-    fn handle_alias_lexer(&mut self, input: &str) {
-        let mut temp_lexers: Vec<Box<Lexer>> = vec![self.lexer.clone()];
-
-        self.lexer = Box::new(Lexer::new(input.to_string()));
-        self.advance();
-
-        while self.current_token.token_type() != &TokenType::EOF {
-            let variable = self.get_variable_from_current_token_value();
-
-            if !variable.is_empty() {
-                temp_lexers.push(self.lexer.clone());
-                
-                self.lexer = Box::new(*temp_lexers.last().unwrap().clone());
-            
-            } else if self.current_token.token_type() == &TokenType::COMMAND {
-                self.handle_command_token();
-            
-            } else if self.current_token.token_type() == &TokenType::STRING {
-                output::print_unknown_command(self.current_token.value());
-                self.advance_until(&[TokenType::EOS]);
-            }
+    /// A statement, if there is one before the token ending it, and the type of that token
+    fn parse_statement(&mut self, in_block: bool) -> Result<(Option<Statement>, TokenType), ExecError> {
+        let token = self.advance()?;
+
+        match token.token_type() {
+            TokenType::EOS | TokenType::EOF => Ok((None, token.token_type)),
+            TokenType::RBRACE if in_block => Ok((None, token.token_type)),
+            _ if is_word(&token, "if") => {
+                let statement = self.parse_if(token)?;
+
+                // what follows the last } has to end the statement, it is left for the next one
+                let next = self.peek()?;
+                match next.token_type() {
+                    TokenType::EOS | TokenType::EOF => {}
+                    TokenType::RBRACE if in_block => {}
+                    _ => return Err(ExecError::Syntax("expected ; after }".to_string()).at(next.span)),
+                }
 
-            self.advance();
-            
-            if temp_lexers.len() == ALIAS_MAX_CALLS {
-                break;
+                Ok((Some(Statement::If(statement)), TokenType::EOS))
             }
-
-            while self.current_token.token_type() == &TokenType::EOF && temp_lexers.len() > 1 {
-                self.lexer = temp_lexers.pop().unwrap();
-                self.advance();
+            _ => {
+                let (invocation, last_token) = self.parse_invocation(token, in_block)?;
+                Ok((Some(Statement::Command(invocation)), last_token))
             }
         }
+    }
 
-        self.lexer = temp_lexers[0].clone();
-        self.advance();
+    /// Reads the arguments following `name` until the end of the statement,<br>
+    /// returning the token type that ended it
+    fn parse_invocation(&mut self, name: Token, in_block: bool) -> Result<(CommandInvocation, TokenType), ExecError> {
+        let mut args = vec!();
+
+        let last_token = loop {
+            let token = self.advance()?;
+
+            let kind = match token.token_type() {
+                TokenType::EOF | TokenType::EOS => break token.token_type,
+                TokenType::RBRACE if in_block => break token.token_type,
+                TokenType::VARIABLE => ArgumentKind::Variable(token.value()[1..].to_string()),
+                TokenType::INTERPOLATED => ArgumentKind::Interpolated(token.parts),
+                _ => ArgumentKind::Literal(token.value),
+            };
+
+            let written = self.lexer.text(token.span).to_string();
+            args.push(Argument { kind, span: token.span, written });
+        };
+
+        let span = args.last().map_or(name.span, |arg: &Argument| name.span.to(arg.span));
+        let invocation = CommandInvocation {
+            name: name.value,
+            name_span: name.span,
+            args,
+            span,
+        };
+
+        Ok((invocation, last_token))
     }
 
-    fn get_arguments(&mut self) -> Vec<String> {
-        let mut arguments = Vec::new();
-
-        while self.current_token.token_type() != &TokenType::EOF && self.current_token.token_type() != &TokenType::EOS {
-            
-            if self.current_token.token_type() == &TokenType::STRING || self.current_token.token_type() == &TokenType::COMMAND {
-                arguments.push(self.current_token.value().to_string());
-            
-            } else if self.current_token.token_type() == &TokenType::VARIABLE {
-            
-                if let Some(value) = self.commands_handler.variables.get(&self.current_token.value()[1..]) {
-                    arguments.push(value.clone());
-            
-                } else {
-                    arguments.push(self.current_token.value().to_string());
+    /// `if <condition> { ... }` followed by an optional `else { ... }` or `else if ...`.<br>
+    /// Braces are only understood as words of their own, so `{echo x}` is not a block
+    fn parse_if(&mut self, if_token: Token) -> Result<IfStatement, ExecError> {
+        let left = self.parse_operand()?;
+
+        let token = self.advance()?;
+        let comparison = match token.token_type() {
+            TokenType::LBRACE => None,
+            _ => {
+                let comparison = Comparison::from_symbol(token.value())
+                    .ok_or_else(|| brace_error(&token, "expected a comparison or {"))?;
+                let right = self.parse_operand()?;
+
+                let token = self.advance()?;
+                if token.token_type() != &TokenType::LBRACE {
+                    return Err(brace_error(&token, "expected { after the condition"));
                 }
+
+                Some((comparison, right))
             }
+        };
 
-            self.advance();
+        let then = Rc::new(self.parse_statements(true)?);
+        let mut span = if_token.span.to(self.last_span);
+
+        // else can be on the line after the }
+        let mut line_break = None;
+        while self.peek()?.token_type() == &TokenType::EOS && self.peek()?.value() == "\n" {
+            line_break = Some(self.advance()?);
+        }
+
+        let mut otherwise = None;
+        if is_word(self.peek()?, "else") {
+            self.advance()?;
+
+            let token = self.advance()?;
+            let block = match token.token_type() {
+                TokenType::LBRACE => self.parse_statements(true)?,
+                _ if is_word(&token, "if") => Script { statements: vec![Statement::If(self.parse_if(token)?)] },
+                _ => return Err(brace_error(&token, "expected { or if after else")),
+            };
+
+            otherwise = Some(Rc::new(block));
+            span = if_token.span.to(self.last_span);
+        } else if let Some(token) = line_break {
+            self.unread(token);
         }
 
-        arguments
+        Ok(IfStatement {
+            condition: Condition { left, comparison },
+            then,
+            otherwise,
+            span,
+        })
+    }
+
+    /// A side of a condition
+    fn parse_operand(&mut self) -> Result<Argument, ExecError> {
+        let token = self.advance()?;
+
+        let kind = match token.token_type() {
+            TokenType::VARIABLE => ArgumentKind::Variable(token.value()[1..].to_string()),
+            TokenType::INTERPOLATED => ArgumentKind::Interpolated(token.parts),
+            TokenType::STRING | TokenType::COMMAND => ArgumentKind::Literal(token.value),
+            _ => return Err(ExecError::Syntax("expected a value in the condition".to_string()).at(token.span)),
+        };
+
+        let written = self.lexer.text(token.span).to_string();
+        Ok(Argument { kind, span: token.span, written })
     }
-}
\ No newline at end of file
+}
+
+/// Syntax error at `token`, which should have been a `{`. Points out when it is one stuck to other text
+fn brace_error(token: &Token, reason: &str) -> ExecError {
+    let reason = if token.value().starts_with('{') { "{ has to be a word of its own" } else { reason };
+    ExecError::Syntax(reason.to_string()).at(token.span)
+}
+
+/// Tells if `token` is `word`, quoted or not
+fn is_word(token: &Token, word: &str) -> bool {
+    matches!(token.token_type(), TokenType::STRING | TokenType::COMMAND) && token.value() == word
+}
@@ -0,0 +1,177 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::ast::Script;
+
+/// Where timers get the time from
+pub trait Clock {
+    /// Seconds since any fixed point, never going back
+    fn now(&self) -> f64;
+}
+
+/// Seconds since it was created
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { start: Instant::now() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+/// Only moves when told to. Clones share the same time, so a clone<br>
+/// can be kept around to move the clock of the interpreter
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<f64>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, seconds: f64) {
+        self.now.set(seconds);
+    }
+
+    pub fn advance(&self, seconds: f64) {
+        self.now.set(self.now.get() + seconds);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+}
+
+/// Identifies a timer so that it can be cancelled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+struct Timer {
+    id: TimerId,
+    script: Rc<Script>,
+    /// When it runs next
+    due: f64,
+    interval: f64,
+    /// Runs left, including the next one
+    remaining: u32,
+}
+
+/// Scripts set to run after some time, checked by `Interpreter::tick`
+pub struct Timers {
+    clock: Box<dyn Clock>,
+    timers: Vec<Timer>,
+    next_id: u64,
+}
+
+impl Timers {
+    /// Timers following a `SystemClock`
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock::new())
+    }
+
+    pub fn with_clock(clock: impl Clock + 'static) -> Self {
+        Timers {
+            clock: Box::new(clock),
+            timers: vec!(),
+            next_id: 0,
+        }
+    }
+
+    /// Replaces where the time comes from. Timers already set keep the time<br>
+    /// they were due at in the previous clock
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    pub fn now(&self) -> f64 {
+        self.clock.now()
+    }
+
+    /// Runs `script` once after `seconds`
+    pub fn delay(&mut self, seconds: f64, script: Script) -> TimerId {
+        self.repeat(seconds, 1, script)
+    }
+
+    /// Runs `script` `count` times, every `interval` seconds starting one interval from now
+    pub fn repeat(&mut self, interval: f64, count: u32, script: Script) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+
+        if count > 0 {
+            self.timers.push(Timer {
+                id,
+                script: Rc::new(script),
+                due: self.now() + interval,
+                interval,
+                remaining: count,
+            });
+        }
+
+        id
+    }
+
+    /// Stops a timer from running again, returning false if it was not set
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != len
+    }
+
+    /// Amount of timers set
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.timers.clear();
+    }
+
+    /// Scripts of the timers that are due, earliest first. A timer runs at most<br>
+    /// once per call, so one that fell behind catches up over the next ones
+    pub(crate) fn take_due(&mut self) -> Vec<Rc<Script>> {
+        let now = self.now();
+
+        let mut due: Vec<(f64, Rc<Script>)> = vec!();
+        self.timers.retain_mut(|timer| {
+            if timer.due > now {
+                return true;
+            }
+
+            due.push((timer.due, timer.script.clone()));
+            timer.due += timer.interval;
+            timer.remaining -= 1;
+            timer.remaining > 0
+        });
+
+        due.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        due.into_iter().map(|(_, script)| script).collect()
+    }
+}
+
+impl Default for Timers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -6,7 +6,14 @@ pub enum TokenType {
     VARIABLE,
     STRING,
     COMMAND,
+    /// Double quoted string containing `$name` or `${name}` references
+    INTERPOLATED,
+    /// `{` standing alone, opening the block of an `if`
+    LBRACE,
+    /// `}` standing alone
+    RBRACE,
     EOF,
+    /// `;` or a line break, ending a statement
     EOS,
 }
 
@@ -17,6 +24,9 @@ impl fmt::Display for TokenType {
             TokenType::VARIABLE => "VARIABLE",
             TokenType::STRING => "STRING",
             TokenType::COMMAND => "COMMAND",
+            TokenType::INTERPOLATED => "INTERPOLATED",
+            TokenType::LBRACE => "LBRACE",
+            TokenType::RBRACE => "RBRACE",
             TokenType::EOF => "EOF",
             TokenType::EOS => "EOS",
         };
@@ -24,10 +34,60 @@ impl fmt::Display for TokenType {
     }
 }
 
+/// Where a piece of source text is. `start` and `end` are byte offsets,<br>
+/// `line` and `column` are 1-based and point at `start`
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Span covering both `self` and `other`, `self` must come first
+    pub fn to(&self, other: Span) -> Span {
+        Span {
+            end: other.end,
+            ..*self
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// A piece of an `INTERPOLATED` string
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum StringPart {
+    Text(String),
+    Variable {
+        /// Name of the variable, without the `$`
+        name: String,
+        /// `$name` or `${name}`, as it was written
+        written: String,
+    },
+}
+
 #[derive(PartialEq, Eq, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
+    pub span: Span,
+    /// Literal text and variables of an `INTERPOLATED` token, empty otherwise
+    pub parts: Vec<StringPart>,
 }
 
 impl Token {
@@ -35,9 +95,15 @@ impl Token {
         Token {
             token_type,
             value,
+            span: Span::default(),
+            parts: vec!(),
         }
     }
 
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
     pub fn token_type(&self) -> &TokenType {
         &self.token_type
     }
@@ -45,8 +111,10 @@ impl Token {
     pub fn value(&self) -> &str {
         &self.value
     }
+}
 
-    pub fn to_string(&self) -> String {
-        format!("Token({}, \"{}\")", self.token_type, self.value)
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Token({}, \"{}\")", self.token_type, self.value)
     }
 }
\ No newline at end of file
@@ -0,0 +1,130 @@
+use std::fmt;
+
+/// Which kind of data a `Value` holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Bool,
+    Int,
+    Float,
+    String,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ValueType::Bool => "bool",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::String => "string",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A typed value, such as the contents of a cvar
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Value {
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Value::Bool(_) => ValueType::Bool,
+            Value::Int(_) => ValueType::Int,
+            Value::Float(_) => ValueType::Float,
+            Value::String(_) => ValueType::String,
+        }
+    }
+
+    /// Parses `text` as a value of type `value_type`.<br>
+    /// Bools accept `1`/`0`, `true`/`false` and `on`/`off`
+    pub fn parse(value_type: ValueType, text: &str) -> Result<Value, String> {
+        match value_type {
+            ValueType::Bool => match text.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" => Ok(Value::Bool(true)),
+                "0" | "false" | "off" => Ok(Value::Bool(false)),
+                _ => Err("expected a bool (1/0, true/false or on/off)".to_string()),
+            },
+            ValueType::Int => text.parse::<i64>().map(Value::Int).map_err(|_| "expected an integer".to_string()),
+            ValueType::Float => text.parse::<f64>().map(Value::Float).map_err(|_| "expected a number".to_string()),
+            ValueType::String => Ok(Value::String(text.to_string())),
+        }
+    }
+
+    /// Numeric view of the value, strings are parsed if possible
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            Value::String(s) => s.parse::<f64>().ok(),
+        }
+    }
+
+    /// Whether the value counts as "on": non-zero numbers, `true` and non-empty strings other than "0"
+    pub fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Int(i) => *i != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::String(s) => !s.is_empty() && s != "0",
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", if *b { "1" } else { "0" }),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value as f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
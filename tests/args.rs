@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use haybcmd::args::{Args, Param, ParamType};
+    use haybcmd::command::{Command, CommandCall};
+    use haybcmd::output::BufferSink;
+    use haybcmd::token::Span;
+    use haybcmd::{ExecError, Interpreter, Value};
+
+    struct Record(Rc<RefCell<Vec<Args>>>);
+    impl CommandCall for Record {
+        fn call(&self, _interpreter: &mut Interpreter, _command: &Command, args: &Args) -> Result<(), ExecError> {
+            self.0.borrow_mut().push(args.clone());
+            Ok(())
+        }
+    }
+
+    fn give_interpreter(calls: &Rc<RefCell<Vec<Args>>>) -> Interpreter {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+
+        let commands_handler = &mut interpreter.commands_handler;
+        commands_handler.register(Command::with_params("give", vec![
+            Param::new("item", ParamType::Enum(vec!["ammo".to_string(), "health".to_string()])),
+            Param::new("count", ParamType::Int).default("1"),
+            Param::new("silent", ParamType::Bool).optional(),
+        ], "gives an item to the player"), Record(calls.clone()));
+
+        commands_handler.register(Command::with_params("bindall", vec![
+            Param::new("command", ParamType::Command),
+            Param::new("weights", ParamType::Float).variadic(),
+        ], "runs a command"), Record(calls.clone()));
+
+        interpreter
+    }
+
+    #[test]
+    fn args_usage() {
+        let calls = Rc::new(RefCell::new(vec!()));
+        let interpreter = give_interpreter(&calls);
+
+        assert_eq!(interpreter.commands_handler.get("give").unwrap().usage, "<item:ammo|health> <count:int?=1> <silent:bool?> - gives an item to the player");
+        assert_eq!(interpreter.commands_handler.get("bindall").unwrap().usage, "<command:command> <weights:float...> - runs a command");
+        assert_eq!(interpreter.commands_handler.get("host_writeconfig").unwrap().usage, "<file?=config.cfg> - saves aliases, binds and archived cvars");
+
+        let give = interpreter.commands_handler.get("give").unwrap();
+        assert_eq!((give.min_args, give.max_args), (1, 3));
+        let bindall = interpreter.commands_handler.get("bindall").unwrap();
+        assert_eq!((bindall.min_args, bindall.max_args), (2, u8::MAX));
+    }
+
+    #[test]
+    fn args_are_converted() {
+        let calls = Rc::new(RefCell::new(vec!()));
+        let mut interpreter = give_interpreter(&calls);
+
+        interpreter.execute("give ammo; give health 25 on; bindall echo 1 2.5").unwrap();
+
+        let calls = calls.borrow();
+        assert_eq!(calls[0], Args::new(vec![Value::from("ammo"), Value::Int(1)]));
+        assert_eq!((calls[1].str(0), calls[1].int(1), calls[1].bool(2)), ("health", 25, true));
+        assert_eq!(calls[2].str(0), "echo");
+        assert_eq!(calls[2].rest(1), &[Value::Float(1.0), Value::Float(2.5)]);
+    }
+
+    #[test]
+    fn args_report_the_failing_argument() {
+        let calls = Rc::new(RefCell::new(vec!()));
+        let mut interpreter = give_interpreter(&calls);
+
+        let err = interpreter.execute("give ammo lots").unwrap_err();
+        assert_eq!(err.root(), &ExecError::InvalidArgument {
+            command: "give".to_string(),
+            argument: "lots".to_string(),
+            reason: "count: expected an integer".to_string(),
+        });
+        assert_eq!(err.span(), Some(Span::new(10, 14, 1, 11)));
+
+        let err = interpreter.execute("give armor").unwrap_err();
+        assert_eq!(err.to_string(), "1:6: give: invalid argument \"armor\": item: expected one of ammo, health");
+
+        assert_eq!(interpreter.execute("bindall nope 1").unwrap_err().root(), &ExecError::UnknownCommand("nope".to_string()));
+        assert_eq!(interpreter.execute("incrementvar missing 0 1 1").unwrap_err().span(), Some(Span::new(13, 20, 1, 14)));
+        assert!(matches!(interpreter.execute("bindall echo").unwrap_err().root(), ExecError::BadArity { min: 2, given: 1, .. }));
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "pc: required parameter b follows an optional one")]
+    fn args_required_after_optional() {
+        Command::with_params("pc", vec![Param::new("a", ParamType::Int).optional(), Param::new("b", ParamType::String)], "");
+    }
+
+    #[test]
+    #[should_panic(expected = "pc: only the last parameter can be variadic, not a")]
+    fn args_variadic_not_last() {
+        Command::with_params("pc", vec![Param::new("a", ParamType::Int).variadic(), Param::new("b", ParamType::String)], "");
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use haybcmd::bindings::Key;
+    use haybcmd::output::BufferSink;
+    use haybcmd::{ExecError, Interpreter};
+
+    #[test]
+    fn bindings_key_names() {
+        assert_eq!(Key::from_name("w"), Key::from_name("W"));
+        assert_eq!(Key::from_name("w").unwrap().code(), 'w' as u32);
+        assert_eq!(Key::from_name("mouse1"), Key::from_code(300));
+        assert_eq!(Key::from_name("Shift").unwrap().to_string(), "SHIFT");
+        assert_eq!(Key::from_name(";").unwrap().to_string(), ";");
+
+        assert_eq!(Key::from_name("MOUSE9"), None);
+        assert_eq!(Key::from_name("é"), None);
+        assert_eq!(Key::from_code(1000), None);
+    }
+
+    #[test]
+    fn bindings_commands() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        interpreter.execute(r#"bind w +forward; bind MOUSE1 "echo pew; echo pew"; bind mouse1; bind f1"#).unwrap();
+        interpreter.execute("bindlist").unwrap();
+        assert_eq!(buffer.contents(), concat!(
+            "MOUSE1 = \"echo pew; echo pew\"\n",
+            "F1 is not bound\n",
+            "amount of binds: 2\n",
+            "MOUSE1 = \"echo pew; echo pew\"\n",
+            "w = \"+forward\"\n",
+        ));
+
+        buffer.clear();
+        interpreter.execute("unbind W; bindlist; unbindall; bindlist").unwrap();
+        assert_eq!(buffer.contents(), "amount of binds: 1\nMOUSE1 = \"echo pew; echo pew\"\namount of binds: 0\n");
+
+        let err = interpreter.execute("bind nokey echo").unwrap_err();
+        assert_eq!(err.root(), &ExecError::InvalidArgument {
+            command: "bind".to_string(),
+            argument: "nokey".to_string(),
+            reason: "unknown key".to_string(),
+        });
+    }
+
+    #[test]
+    fn bindings_press_and_release() {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+        let log = Rc::new(RefCell::new(vec!()));
+
+        let (press_log, release_log) = (log.clone(), log.clone());
+        interpreter.register_button("attack",
+            move |_interpreter, event| {
+                press_log.borrow_mut().push(format!("+attack {:?}", event.map(|event| event.key_code)));
+                Ok(())
+            },
+            move |_interpreter, event| {
+                release_log.borrow_mut().push(format!("-attack {:?}", event.map(|event| event.key_code)));
+                Ok(())
+            },
+        );
+
+        let mouse1 = Key::from_name("MOUSE1").unwrap();
+        interpreter.execute("bind mouse1 +attack").unwrap();
+
+        // holding a key does not press it again
+        interpreter.press_key(mouse1, 1.0).unwrap();
+        interpreter.press_key(mouse1, 1.1).unwrap();
+
+        // releasing undoes what was pressed even after rebinding
+        interpreter.execute("bind mouse1 echo rebound").unwrap();
+        interpreter.release_key(mouse1, 1.5).unwrap();
+        interpreter.release_key(mouse1, 1.6).unwrap();
+
+        // unbound keys do nothing
+        interpreter.press_key(Key::from_name("F12").unwrap(), 2.0).unwrap();
+
+        assert_eq!(*log.borrow(), vec!["+attack Some(300)", "-attack Some(300)"]);
+
+        // + commands in if blocks get the key too, and are released whatever the condition is by then
+        log.borrow_mut().clear();
+        interpreter.commands_handler.register_cvar("armed", true, "", haybcmd::cvar::FCVAR_NONE);
+        interpreter.execute("bind mouse1 if $armed { +attack } else { echo unarmed }").unwrap();
+        interpreter.press_key(mouse1, 3.0).unwrap();
+        interpreter.execute("armed 0").unwrap();
+        interpreter.release_key(mouse1, 3.5).unwrap();
+        assert_eq!(*log.borrow(), vec!["+attack Some(300)", "-attack Some(300)"]);
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::output::BufferSink;
+    use haybcmd::token::Span;
+    use haybcmd::{ExecError, Interpreter};
+
+    #[test]
+    fn cbuf_wait() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        interpreter.execute("echo a; wait; echo b; wait 2; echo c; wait 0; echo d").unwrap();
+        interpreter.execute("echo other").unwrap();
+        assert_eq!(buffer.contents(), "a\nother\n");
+        assert_eq!(interpreter.cbuf.len(), 1);
+
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "a\nother\nb\n");
+
+        interpreter.tick().unwrap();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "a\nother\nb\nc\nd\n");
+        assert!(interpreter.cbuf.is_empty());
+
+        let err = interpreter.execute("wait -1").unwrap_err();
+        assert_eq!(err.to_string(), "1:6: wait: invalid argument \"-1\": ticks: can not be negative");
+        let err = interpreter.execute("wait 99999999999").unwrap_err();
+        assert_eq!(err.to_string(), "1:6: wait: invalid argument \"99999999999\": ticks: can not be more than 4294967295");
+    }
+
+    #[test]
+    fn cbuf_wait_in_aliases() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        interpreter.execute(r#"alias combo "echo down; wait; echo up"; combo; echo after"#).unwrap();
+        assert_eq!(buffer.contents(), "down\n");
+
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "down\nup\nafter\n");
+
+        // an alias calling itself after a wait goes on for as long as it is not cleared
+        buffer.clear();
+        interpreter.execute(r#"alias spin "echo x; wait; spin"; spin"#).unwrap();
+        for _ in 0..60000 {
+            interpreter.tick().unwrap();
+        }
+        assert_eq!(buffer.contents().len(), 60001 * 2);
+
+        interpreter.cbuf.clear();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents().len(), 60001 * 2);
+    }
+
+    #[test]
+    fn cbuf_errors_after_wait() {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+
+        interpreter.execute(r#"alias broken "wait; nope"; echo ok; broken"#).unwrap();
+        interpreter.execute("wait; nope; echo never").unwrap();
+
+        // both scripts run, the first error is returned and neither goes on
+        let err = interpreter.tick().unwrap_err();
+        assert_eq!(err.root(), &ExecError::UnknownCommand("nope".to_string()));
+        assert_eq!(err.span(), Some(Span::new(36, 42, 1, 37)));
+        assert!(interpreter.cbuf.is_empty());
+    }
+
+    #[test]
+    fn cbuf_enqueue() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        interpreter.execute("echo a; wait; echo b").unwrap();
+        interpreter.enqueue("echo queued\necho lines").unwrap();
+        interpreter.execute("echo now").unwrap();
+        assert_eq!(buffer.contents(), "a\nnow\n");
+        assert_eq!(interpreter.enqueue("echo \"open").unwrap_err().root(), &ExecError::UnterminatedString);
+
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "a\nnow\nb\nqueued\nlines\n");
+        assert!(interpreter.cbuf.is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::context::Context;
+    use haybcmd::output::BufferSink;
+    use haybcmd::{ExecError, Interpreter};
+
+    #[derive(Debug, PartialEq)]
+    struct Player {
+        health: i64,
+    }
+
+    struct Sound(Vec<String>);
+
+    #[haybcmd::command(usage = "heals the player")]
+    fn heal(interpreter: &mut Interpreter, amount: i64) -> Result<(), ExecError> {
+        interpreter.context.require_mut::<Player>()?.health += amount;
+        interpreter.context.require_mut::<Sound>()?.0.push("heal.wav".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn context_type_map() {
+        let mut context = Context::new();
+        assert_eq!(context.insert(Player { health: 100 }), None);
+        assert_eq!(context.insert(Player { health: 50 }), Some(Player { health: 100 }));
+        assert!(context.contains::<Player>());
+        assert!(context.get::<Sound>().is_none());
+
+        context.get_mut::<Player>().unwrap().health -= 10;
+        assert_eq!(context.get::<Player>(), Some(&Player { health: 40 }));
+        assert_eq!(context.remove::<Player>(), Some(Player { health: 40 }));
+        assert!(!context.contains::<Player>());
+    }
+
+    #[test]
+    fn context_reaches_commands() {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+        interpreter.register_definition(heal);
+        interpreter.register("hurt", |interpreter, _args| {
+            interpreter.context.require_mut::<Player>()?.health -= 30;
+            Ok(())
+        });
+
+        interpreter.context.insert(Player { health: 100 });
+        interpreter.execute("hurt; hurt").unwrap();
+        assert_eq!(interpreter.context.get::<Player>(), Some(&Player { health: 40 }));
+
+        let err = interpreter.execute("heal 5").unwrap_err();
+        assert!(matches!(err.root(), ExecError::MissingContext(name) if name.ends_with("Sound")));
+
+        interpreter.context.insert(Sound(vec!()));
+        interpreter.execute("heal 5").unwrap();
+        assert_eq!(interpreter.context.get::<Player>(), Some(&Player { health: 50 }));
+        assert_eq!(interpreter.context.get::<Sound>().unwrap().0, vec!["heal.wav"]);
+    }
+}
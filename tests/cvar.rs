@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::cvar::{FCVAR_ARCHIVE, FCVAR_CHEAT, FCVAR_READONLY};
+    use haybcmd::output::BufferSink;
+    use haybcmd::{ExecError, Interpreter, Value};
+
+    #[test]
+    fn cvar_set_and_query() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        interpreter.commands_handler.register_cvar("sensitivity", 3.0, "mouse sensitivity", FCVAR_ARCHIVE).unwrap().set_bounds(Some(0.1), Some(10.0)).unwrap();
+        interpreter.commands_handler.register_cvar("name", "player", "your name", FCVAR_ARCHIVE);
+
+        interpreter.execute("sensitivity 2.5; name \"John Smith\"").unwrap();
+        assert_eq!(interpreter.commands_handler.get_cvar("sensitivity").unwrap().value(), &Value::Float(2.5));
+        assert_eq!(interpreter.commands_handler.get_cvar("name").unwrap().value(), &Value::String("John Smith".to_string()));
+
+        interpreter.execute("sensitivity 50").unwrap();
+        assert_eq!(interpreter.commands_handler.get_cvar("sensitivity").unwrap().value(), &Value::Float(10.0));
+
+        interpreter.execute("sensitivity").unwrap();
+        assert_eq!(buffer.contents(), "sensitivity = \"10\" (def. \"3\", min. 0.1, max. 10) - mouse sensitivity\n");
+    }
+
+    #[test]
+    fn cvar_types_and_flags() {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+
+        interpreter.commands_handler.register_cvar("fps_max", 300, "", FCVAR_ARCHIVE);
+        interpreter.commands_handler.register_cvar("version", "1.0", "", FCVAR_READONLY);
+        interpreter.commands_handler.register_cvar("noclip_speed", 5.0, "", FCVAR_CHEAT);
+        assert!(interpreter.commands_handler.register_cvar("echo", 1, "", FCVAR_ARCHIVE).is_none());
+
+        assert!(matches!(interpreter.execute("fps_max fast").map_err(|err| err.root().clone()), Err(ExecError::InvalidArgument { .. })));
+        assert_eq!(interpreter.execute("version 2.0").map_err(|err| err.root().clone()), Err(ExecError::ReadOnlyCvar("version".to_string())));
+        assert_eq!(interpreter.execute("noclip_speed 10").map_err(|err| err.root().clone()), Err(ExecError::CheatProtected("noclip_speed".to_string())));
+
+        interpreter.execute("sv_cheats 1; noclip_speed 10").unwrap();
+        assert_eq!(interpreter.commands_handler.get_cvar("noclip_speed").unwrap().value(), &Value::Float(10.0));
+
+        interpreter.commands_handler.set_cvar("version", "2.0").unwrap();
+        assert_eq!(interpreter.commands_handler.get_cvar("version").unwrap().value(), &Value::String("2.0".to_string()));
+
+        interpreter.execute("developer 2").unwrap();
+        assert_eq!(interpreter.output.developer(), 2);
+    }
+
+    #[test]
+    fn cvar_callbacks() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let callback_changes = changes.clone();
+
+        interpreter.commands_handler.register_cvar("fps_max", 300, "", FCVAR_ARCHIVE).unwrap().add_callback(move |old, new| {
+            callback_changes.borrow_mut().push((old.clone(), new.clone()));
+
+            match new {
+                Value::Int(0) => Err("fps_max can not be 0".to_string()),
+                Value::Int(fps) if *fps > 1000 => {
+                    *new = Value::Int(1000);
+                    Ok(())
+                }
+                _ => Ok(()),
+            }
+        });
+
+        interpreter.execute("fps_max 144").unwrap();
+        interpreter.commands_handler.set_cvar("fps_max", 5000).unwrap();
+        assert!(matches!(interpreter.execute("fps_max 0").map_err(|err| err.root().clone()), Err(ExecError::InvalidArgument { .. })));
+        interpreter.execute("fps_max 1000").unwrap(); // unchanged, so no callback
+
+        assert_eq!(interpreter.commands_handler.get_cvar("fps_max").unwrap().value(), &Value::Int(1000));
+        assert_eq!(*changes.borrow(), vec![
+            (Value::Int(300), Value::Int(144)),
+            (Value::Int(144), Value::Int(5000)),
+            (Value::Int(1000), Value::Int(0)),
+        ]);
+
+        // clamping to new bounds is a change like any other
+        changes.borrow_mut().clear();
+        let cvar = interpreter.commands_handler.get_cvar_mut("fps_max").unwrap();
+        cvar.set_bounds(Some(30.0), Some(500.0)).unwrap();
+        assert_eq!(cvar.value(), &Value::Int(500));
+        assert_eq!(cvar.set_bounds(None, Some(0.0)).unwrap_err(), "fps_max can not be 0");
+        assert_eq!((cvar.value(), cvar.min(), cvar.max()), (&Value::Int(500), Some(30.0), Some(500.0)));
+        assert_eq!(*changes.borrow(), vec![
+            (Value::Int(1000), Value::Int(500)),
+            (Value::Int(500), Value::Int(0)),
+        ]);
+    }
+}
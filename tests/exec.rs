@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use haybcmd::cvar::FCVAR_ARCHIVE;
+    use haybcmd::lexer::LexerOptions;
+    use haybcmd::output::BufferSink;
+    use haybcmd::{ExecError, Interpreter, Value};
+
+    fn config_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("haybcmd-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn exec_nested_files() {
+        let dir = config_dir("exec-nested");
+        fs::write(dir.join("autoexec.cfg"), "echo first\nexec binds\nfps_max 144\n").unwrap();
+        fs::write(dir.join("binds.cfg"), "echo second\n").unwrap();
+
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("fps_max", 300, "", FCVAR_ARCHIVE);
+        interpreter.exec_paths = vec![dir.clone()];
+
+        interpreter.execute("exec autoexec").unwrap();
+
+        assert_eq!(buffer.contents(), "first\nsecond\n");
+        assert_eq!(interpreter.commands_handler.get_cvar("fps_max").unwrap().value(), &Value::Int(144));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exec_errors() {
+        let dir = config_dir("exec-errors");
+        fs::write(dir.join("a.cfg"), "echo a\nexec b.cfg\n").unwrap();
+        fs::write(dir.join("b.cfg"), "exec a.cfg\n").unwrap();
+        fs::write(dir.join("typo.cfg"), "echo ok\n\nold_removed_cvar 1\nfps_max 144; nope\necho \"open\necho after\n").unwrap();
+
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("fps_max", 300, "", FCVAR_ARCHIVE);
+        interpreter.exec_paths = vec![dir.clone()];
+
+        let a = dir.join("a.cfg").display().to_string();
+        let b = dir.join("b.cfg").display().to_string();
+        interpreter.exec_file("a.cfg").unwrap();
+        assert_eq!(buffer.contents(), format!("a\n{}:1:1: \"{}\" is already being executed\n", b, a));
+
+        // errors are reported with their line and the statements after them still run
+        buffer.clear();
+        let typo = dir.join("typo.cfg").display().to_string();
+        interpreter.exec_file("typo").unwrap();
+        assert_eq!(buffer.contents(), format!(
+            "{}:5:6: unterminated string\nok\n{}:3:1: unknown command \"old_removed_cvar\"\n{}:4:14: unknown command \"nope\"\nafter\n",
+            typo, typo, typo
+        ));
+        assert_eq!(interpreter.commands_handler.get_cvar("fps_max").unwrap().value(), &Value::Int(144));
+
+        assert!(matches!(interpreter.execute("exec missing").unwrap_err().root(), ExecError::Io { .. }));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn write_config_round_trip() {
+        use haybcmd::cvar::{FCVAR_NONE, FCVAR_READONLY};
+
+        let dir = config_dir("write-config");
+
+        let register = |interpreter: &mut Interpreter| {
+            interpreter.commands_handler.register_cvar("sensitivity", 3.0, "", FCVAR_ARCHIVE);
+            interpreter.commands_handler.register_cvar("name", "player", "", FCVAR_ARCHIVE);
+            interpreter.commands_handler.register_cvar("cl_showfps", false, "", FCVAR_ARCHIVE);
+            interpreter.commands_handler.register_cvar("version", "1.0", "", FCVAR_ARCHIVE | FCVAR_READONLY);
+            interpreter.commands_handler.register_cvar("net_graph", 0, "", FCVAR_NONE);
+            interpreter.exec_paths = vec![dir.clone()];
+        };
+
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+        register(&mut interpreter);
+        interpreter.commands_handler.set_cvar("sensitivity", 1.25).unwrap();
+        interpreter.commands_handler.set_cvar("name", r#"the "best" \ player; echo hacked"#).unwrap();
+        interpreter.commands_handler.set_cvar("cl_showfps", true).unwrap();
+        interpreter.commands_handler.set_cvar("net_graph", 3).unwrap();
+        interpreter.execute(r#"alias greet "echo \"hi\"; echo \$hp""#).unwrap();
+        interpreter.execute(r#"bind mouse1 "echo \"pew\""; bind ";" greet"#).unwrap();
+        interpreter.execute("host_writeconfig").unwrap();
+
+        let written = fs::read_to_string(dir.join("config.cfg")).unwrap();
+        assert_eq!(written, concat!(
+            "alias \"greet\" \"echo \\\"hi\\\"; echo \\$hp\"\n",
+            "bind \";\" \"greet\"\n",
+            "bind \"MOUSE1\" \"echo \\\"pew\\\"\"\n",
+            "cl_showfps \"1\"\n",
+            "name \"the \\\"best\\\" \\\\ player; echo hacked\"\n",
+            "sensitivity \"1.25\"\n",
+        ));
+
+        let buffer = BufferSink::new();
+        let mut restored = Interpreter::with_output(buffer.clone());
+        register(&mut restored);
+        restored.execute("exec config").unwrap();
+
+        for name in ["sensitivity", "name", "cl_showfps"] {
+            assert_eq!(restored.commands_handler.get_cvar(name).unwrap().value(), interpreter.commands_handler.get_cvar(name).unwrap().value());
+        }
+        assert_eq!(restored.commands_handler.get_cvar("net_graph").unwrap().value(), &Value::Int(0));
+        assert_eq!(restored.commands_handler.variables, interpreter.commands_handler.variables);
+        assert_eq!(restored.commands_handler.bindings.sorted(), interpreter.commands_handler.bindings.sorted());
+        assert_eq!(buffer.contents(), "");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exec_waits_for_the_rest_of_the_file() {
+        let dir = config_dir("exec-wait");
+        fs::write(dir.join("combo.cfg"), "echo 1\nwait 2\necho 2\n").unwrap();
+
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.exec_paths = vec![dir.clone()];
+
+        // like in an alias, the wait holds back what comes after the exec too
+        interpreter.execute("exec combo; echo after").unwrap();
+        assert_eq!(buffer.contents(), "1\n");
+
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "1\n");
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "1\n2\nafter\n");
+
+        // exec_file has no caller to hold back
+        buffer.clear();
+        interpreter.exec_file("combo").unwrap();
+        interpreter.execute("echo after").unwrap();
+        interpreter.tick().unwrap();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "1\nafter\n2\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exec_multi_line_if() {
+        let dir = config_dir("exec-if");
+        fs::write(dir.join("mode.cfg"), "if $mode == 1 {\n    echo one\n    echo again\n}\nelse if $mode == 2 {\n    echo two\n}\n\nelse {\n    echo other\n}\necho done\nif $mode {\n}\n\necho end\n").unwrap();
+
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("mode", 1, "", haybcmd::cvar::FCVAR_NONE);
+        interpreter.exec_paths = vec![dir.clone()];
+
+        interpreter.execute("exec mode; mode 2; exec mode; mode 3; exec mode").unwrap();
+        assert_eq!(buffer.contents(), "one\nagain\ndone\nend\ntwo\ndone\nend\nother\ndone\nend\n");
+
+        buffer.clear();
+        fs::write(dir.join("broken.cfg"), "echo ok\nif $mode {\n    echo never\n").unwrap();
+        interpreter.execute("exec broken").unwrap();
+        assert_eq!(buffer.contents(), format!("{}:4:1: syntax error: expected }} before the end of the input\nok\n", dir.join("broken.cfg").display()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn exec_block_comments() {
+        let dir = config_dir("exec-comments");
+        fs::write(dir.join("commented.cfg"), "/* header\n   spanning lines */\necho ok /* trailing\n */\necho \"/* kept */\"\n/* unclosed\n").unwrap();
+
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.lexer_options = LexerOptions { hash_comments: false, block_comments: true };
+        interpreter.exec_paths = vec![dir.clone()];
+
+        interpreter.exec_file("commented").unwrap();
+        assert_eq!(buffer.contents(), format!("{}:6:1: unterminated comment\nok\n/* kept */\n", dir.join("commented.cfg").display()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use haybcmd::input::KeyEvent;
+    use haybcmd::output::BufferSink;
+    use haybcmd::Interpreter;
+
+    fn recording_interpreter() -> (Interpreter, Rc<RefCell<Vec<String>>>) {
+        let mut interpreter = Interpreter::with_output(BufferSink::new());
+        let log = Rc::new(RefCell::new(vec!()));
+
+        for name in ["forward", "jump"] {
+            let (press_log, release_log) = (log.clone(), log.clone());
+            assert!(interpreter.register_button(name,
+                move |_interpreter, event| {
+                    press_log.borrow_mut().push(format!("+{} {:?}", name, event));
+                    Ok(())
+                },
+                move |_interpreter, event| {
+                    release_log.borrow_mut().push(format!("-{} {:?}", name, event));
+                    Ok(())
+                },
+            ));
+        }
+
+        (interpreter, log)
+    }
+
+    #[test]
+    fn input_paired_commands() {
+        let (mut interpreter, log) = recording_interpreter();
+        let event = KeyEvent::new(87, 1.5);
+
+        interpreter.press_command("+forward", event).unwrap();
+        interpreter.release_command("+forward", KeyEvent::new(87, 2.0)).unwrap();
+        interpreter.execute("+jump; -jump").unwrap();
+
+        assert_eq!(*log.borrow(), vec![
+            "+forward Some(KeyEvent { key_code: 87, timestamp: 1.5 })",
+            "-forward Some(KeyEvent { key_code: 87, timestamp: 2.0 })",
+            "+jump None",
+            "-jump None",
+        ]);
+
+        assert!(!interpreter.register_button("forward", |_, _| Ok(()), |_, _| Ok(())));
+        assert!(interpreter.commands_handler.get("+forward").unwrap().usage.starts_with("<key:int?> <time:float?>"));
+    }
+
+    #[test]
+    fn input_plus_aliases() {
+        let (mut interpreter, log) = recording_interpreter();
+        interpreter.execute(r#"alias +leap "+jump; +forward"; alias -leap "-jump; -forward"; alias +nothing "+jump""#).unwrap();
+
+        interpreter.press_command("+leap", KeyEvent::new(32, 3.0)).unwrap();
+        interpreter.release_command("+leap", KeyEvent::new(32, 4.0)).unwrap();
+        assert_eq!(*log.borrow(), vec!["+jump None", "+forward None", "-jump None", "-forward None"]);
+
+        // no -nothing, so releasing does nothing
+        log.borrow_mut().clear();
+        interpreter.press_command("+nothing", KeyEvent::new(1, 0.0)).unwrap();
+        interpreter.release_command("+nothing", KeyEvent::new(1, 0.5)).unwrap();
+        assert_eq!(*log.borrow(), vec!["+jump None"]);
+
+        // plain commands run on press only and get no key arguments
+        log.borrow_mut().clear();
+        interpreter.press_command("+jump; -jump; echo pressed", KeyEvent::new(2, 1.0)).unwrap();
+        interpreter.release_command("+jump; -jump; echo pressed", KeyEvent::new(2, 1.25)).unwrap();
+        assert_eq!(*log.borrow(), vec![
+            "+jump Some(KeyEvent { key_code: 2, timestamp: 1.0 })",
+            "-jump None",
+            "-jump Some(KeyEvent { key_code: 2, timestamp: 1.25 })",
+        ]);
+    }
+}
@@ -2,19 +2,274 @@
 mod tests {
     #[test]
     fn interpreter() {
-        let (commands_handler, commands_funcs) = haybcmd::init();
+        let mut interpreter = haybcmd::Interpreter::new();
         
         //haybcmd::add_cvar("tf_fuck", tf_fuck, "1 or 0"); // - type: boolean - 1/0 is a default description for bool cvars(\n the rest of the description)
         
-        let input = String::from(r#"
+        let input = r#"
             echo "Hello, World!";
             alias $var echo "This is a variable: $var";
             $var "Hello, Variable!";
             variables;
-        "#);
+        "#;
 
-        haybcmd::parse(commands_handler, commands_funcs, input);
+        let _ = interpreter.execute(input);
+    }
+
+    #[test]
+    fn interpreter_keeps_state() {
+        let buffer = haybcmd::output::BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+
+        interpreter.commands_handler.variables.insert("greet".to_string(), "echo hi".to_string());
+        interpreter.execute("greet").unwrap();
+        interpreter.execute("greet").unwrap();
+
+        assert_eq!(buffer.contents(), "hi\nhi\n");
+    }
+
+    #[test]
+    fn interpreter_output_sinks() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use haybcmd::output::{BufferSink, CallbackSink, TeeSink};
+
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let callback_lines = lines.clone();
+        let buffer = BufferSink::new();
+
+        let mut tee = TeeSink::new();
+        tee.push(buffer.clone());
+        tee.push(CallbackSink::new(move |message| callback_lines.borrow_mut().push(message.text.to_string())));
+
+        let mut interpreter = haybcmd::Interpreter::with_output(tee);
+        interpreter.execute("help echo").unwrap();
+
+        assert_eq!(buffer.contents(), "echo <message...> - echoes a message to the console\n");
+        assert_eq!(lines.borrow().concat(), buffer.contents());
+    }
+
+    #[test]
+    fn interpreter_errors() {
+        use haybcmd::ExecError;
+
+        let mut interpreter = haybcmd::Interpreter::new();
+
+        assert_eq!(interpreter.execute("nope").map_err(|err| err.root().clone()), Err(ExecError::UnknownCommand("nope".to_string())));
+        assert_eq!(interpreter.execute("echo \"open").map_err(|err| err.root().clone()), Err(ExecError::UnterminatedString));
+        assert_eq!(interpreter.execute("variable missing").map_err(|err| err.root().clone()), Err(ExecError::UnknownVariable("missing".to_string())));
+        assert!(matches!(interpreter.execute("variable").map_err(|err| err.root().clone()), Err(ExecError::BadArity { given: 0, .. })));
+        assert_eq!(interpreter.execute("help nope").map_err(|err| err.root().clone()), Err(ExecError::UnknownCommand("nope".to_string())));
+    }
+
+    #[test]
+    fn interpreter_error_spans() {
+        use haybcmd::token::Span;
+
+        let mut interpreter = haybcmd::Interpreter::with_output(haybcmd::output::BufferSink::new());
+        interpreter.commands_handler.register_cvar("fps_max", 300, "", haybcmd::cvar::FCVAR_ARCHIVE);
+
+        let err = interpreter.execute("echo ok; nope").unwrap_err();
+        assert_eq!(err.span(), Some(Span::new(9, 13, 1, 10)));
+        assert_eq!(err.to_string(), "1:10: unknown command \"nope\"");
+
+        assert_eq!(interpreter.execute("echo ok;\nfps_max   fast").unwrap_err().span(), Some(Span::new(19, 23, 2, 11)));
+        assert_eq!(interpreter.execute("echo ok; echo \"nee").unwrap_err().span(), Some(Span::new(14, 18, 1, 15)));
+        assert_eq!(interpreter.execute("echo ok; echo \"née").unwrap_err().span(), Some(Span::new(14, 19, 1, 15)));
+        assert_eq!(interpreter.execute("help   nothing").unwrap_err().span(), Some(Span::new(7, 14, 1, 8)));
+        assert_eq!(interpreter.execute("help   some thing").unwrap_err().span(), Some(Span::new(0, 17, 1, 1)));
+    }
+
+    #[test]
+    fn interpreter_interpolation() {
+        use haybcmd::{ExecError, UnknownVariablePolicy};
+
+        let buffer = haybcmd::output::BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("hp", 100, "", haybcmd::cvar::FCVAR_NONE);
+        interpreter.commands_handler.variables.insert("name".to_string(), "bob".to_string());
+
+        interpreter.execute(r#"echo "hp is $hp""#).unwrap();
+        interpreter.execute(r#"echo "${name}s hp: ${hp}%""#).unwrap();
+        interpreter.execute(r#"echo "\$hp costs $ 5"; echo '$hp'"#).unwrap();
+        interpreter.execute(r#"echo "$missing and ${missing}""#).unwrap();
+        assert_eq!(buffer.contents(), "hp is 100\nbobs hp: 100%\n$hp costs $ 5\n$hp\n$missing and ${missing}\n");
+
+        // kept text is data, so it must not change
+        interpreter.commands_handler.register_cvar("title", "", "", haybcmd::cvar::FCVAR_NONE);
+        interpreter.execute(r#"title "a $x b ${y}c""#).unwrap();
+        assert_eq!(interpreter.commands_handler.get_cvar("title").unwrap().value().to_string(), "a $x b ${y}c");
+
+        buffer.clear();
+        interpreter.unknown_variables = UnknownVariablePolicy::Empty;
+        interpreter.execute(r#"echo "[$missing]""#).unwrap();
+        assert_eq!(buffer.contents(), "[]\n");
+
+        interpreter.unknown_variables = UnknownVariablePolicy::Error;
+        let err = interpreter.execute(r#"echo ok; echo "hp $missing""#).unwrap_err();
+        assert_eq!(err.root(), &ExecError::UnknownVariable("missing".to_string()));
+        assert_eq!(err.span(), Some(haybcmd::token::Span::new(14, 27, 1, 15)));
+        assert_eq!(interpreter.execute("echo $missing").map_err(|err| err.root().clone()), Err(ExecError::UnknownVariable("missing".to_string())));
+    }
+
+    #[test]
+    fn interpreter_base_commands() {
+        use haybcmd::ExecError;
+
+        let buffer = haybcmd::output::BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+        let output = |interpreter: &mut haybcmd::Interpreter, input: &str| {
+            buffer.clear();
+            interpreter.execute(input).map(|_| buffer.contents())
+        };
+
+        assert_eq!(output(&mut interpreter, "help echo"), Ok("echo <message...> - echoes a message to the console\n".to_string()));
+        assert!(output(&mut interpreter, "help").unwrap().contains("incrementvar <var:variable> <minValue:float> <maxValue:float> <delta:float> - increments the value of a variable\n"));
+
+        assert_eq!(output(&mut interpreter, "echo   hello   world"), Ok("hello world\n".to_string()));
+        assert_eq!(output(&mut interpreter, "echo \"two  spaces\" kept"), Ok("two  spaces kept\n".to_string()));
+
+        assert_eq!(output(&mut interpreter, "alias greet \"echo hi; echo there\"; greet"), Ok("hi\nthere\n".to_string()));
+        assert_eq!(output(&mut interpreter, "alias shout echo HEY; shout"), Ok("HEY\n".to_string()));
+        // the rest of the line keeps its quotes, so quoted text stays data when the alias runs
+        assert_eq!(output(&mut interpreter, "alias semi echo \"a;b\" '$hp'  \"c  d\"; variable semi; semi"), Ok("semi = \"echo \"a;b\" '$hp' \"c  d\"\"\na;b $hp c  d\n".to_string()));
+        assert_eq!(output(&mut interpreter, "bind w echo \"x y\" z; bind w"), Ok("w = \"echo \"x y\" z\"\n".to_string()));
+        assert_eq!(output(&mut interpreter, "alias semi; unbind w"), Ok("".to_string()));
+        assert_eq!(output(&mut interpreter, "alias shout; variable shout").map_err(|err| err.root().clone()), Err(ExecError::UnknownVariable("shout".to_string())));
+
+        assert_eq!(output(&mut interpreter, "variables"), Ok("amount of variables: 1\ngreet = \"echo hi; echo there\"\n".to_string()));
+        assert_eq!(output(&mut interpreter, "variable greet"), Ok("greet = \"echo hi; echo there\"\n".to_string()));
+
+        assert_eq!(output(&mut interpreter, "alias n 9; incrementvar n 0 10 1; variable n"), Ok("n = \"10\"\n".to_string()));
+        assert_eq!(output(&mut interpreter, "incrementvar n 0 10 1; variable n"), Ok("n = \"0\"\n".to_string()));
+        assert_eq!(output(&mut interpreter, "incrementvar developer 0 2 1; developer"), Ok("developer = \"1\" (def. \"0\", min. 0, max. 255) - shows developer messages up to this level\n".to_string()));
+        assert!(matches!(output(&mut interpreter, "incrementvar n 0 10").unwrap_err().root(), ExecError::BadArity { given: 3, .. }));
+
+        assert!(output(&mut interpreter, "cvarlist").unwrap().starts_with("amount of cvars: 2\ndeveloper = "));
+
+        assert!(matches!(output(&mut interpreter, "exec missing").unwrap_err().root(), ExecError::Io { .. }));
+        assert!(matches!(output(&mut interpreter, "exec a b").unwrap_err().root(), ExecError::BadArity { given: 2, .. }));
+        assert!(matches!(output(&mut interpreter, "host_writeconfig a b").unwrap_err().root(), ExecError::BadArity { given: 2, .. }));
+    }
+
+    #[test]
+    fn interpreter_register_closures() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+        use haybcmd::args::{Param, ParamType};
+        use haybcmd::command::Command;
+        use haybcmd::output::OutputSink;
+
+        let buffer = haybcmd::output::BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+
+        let count = Rc::new(Cell::new(0));
+        let counter = count.clone();
+        assert!(interpreter.register("count", move |_interpreter, args| {
+            counter.set(counter.get() + args.len());
+            Ok(())
+        }));
+        assert!(!interpreter.register("echo", |_interpreter, _args| Ok(())));
+        assert!(!interpreter.register("developer", |_interpreter, _args| Ok(())));
+        assert!(!interpreter.register("else", |_interpreter, _args| Ok(())));
+        assert!(interpreter.commands_handler.register_cvar("if", 1, "", haybcmd::cvar::FCVAR_NONE).is_none());
+        assert_eq!(
+            interpreter.execute("alias if \"echo hi\"").unwrap_err().to_string(),
+            "1:7: alias: invalid argument \"if\": varName is a keyword, therefore this variable can not be created"
+        );
+
+        assert!(interpreter.register_command(Command::with_params("double", vec![Param::new("n", ParamType::Int)], "prints twice n"), |interpreter, args| {
+            let doubled = args.int(0) * 2;
+            interpreter.output.println(&doubled.to_string());
+            Ok(())
+        }));
+
+        interpreter.execute("count a b c; count; count d; double 21; echo still here").unwrap();
+        assert_eq!(count.get(), 4);
+        assert_eq!(buffer.contents(), "42\nstill here\n");
+
+        // a replaced command calls its own function, not the one registered first
+        assert!(interpreter.commands_handler.delete("count"));
+        interpreter.register("count", |interpreter, _args| {
+            interpreter.output.println("replaced");
+            Ok(())
+        });
+        interpreter.execute("count x").unwrap();
+        assert_eq!(count.get(), 4);
+        assert!(buffer.contents().ends_with("replaced\n"));
+
+        // commands keep their function even in a handler of their own
+        let mut handler = haybcmd::command::CommandsHandler::new();
+        assert!(handler.register(Command::new("alone", 0, 0, ""), |interpreter: &mut haybcmd::Interpreter, _args: &haybcmd::args::Args| {
+            interpreter.output.println("alone");
+            Ok(())
+        }));
+        assert_eq!(handler.iter().map(|command| command.name.as_str()).collect::<Vec<_>>(), vec!["alone"]);
+        let (command, func) = handler.get_shared("alone").unwrap();
+        func.call(&mut interpreter, &command, &haybcmd::args::Args::default()).unwrap();
+
+        interpreter.call_command("double", &haybcmd::args::Args::new(vec![haybcmd::Value::Int(5)])).unwrap();
+        assert!(interpreter.call_command("alone", &haybcmd::args::Args::default()).is_err());
+        assert!(buffer.contents().ends_with("replaced\nalone\n10\n"));
+    }
+
+    #[test]
+    fn interpreter_severity() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use haybcmd::output::{CallbackSink, Color, OutputSink, Severity};
+
+        let messages = Rc::new(RefCell::new(Vec::new()));
+        let callback_messages = messages.clone();
+
+        let mut interpreter = haybcmd::Interpreter::with_output(CallbackSink::new(move |message| {
+            callback_messages.borrow_mut().push((message.severity, message.color, message.text.to_string()));
+        }));
+
+        interpreter.output.warning("careful\n");
+        interpreter.output.dev_msg(1, "hidden\n");
+        interpreter.commands_handler.set_cvar("developer", 1).unwrap();
+        interpreter.output.dev_msg(1, "shown\n");
+        interpreter.output.dev_msg(2, "too verbose\n");
+        interpreter.output.color_print(Color::new(0, 255, 0), "green\n");
+
+        assert_eq!(*messages.borrow(), vec![
+            (Severity::Warning, None, "careful\n".to_string()),
+            (Severity::Developer(1), None, "shown\n".to_string()),
+            (Severity::Info, Some(Color::new(0, 255, 0)), "green\n".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn interpreter_conditions() {
+        use haybcmd::cvar::FCVAR_NONE;
+        use haybcmd::token::Span;
+
+        let buffer = haybcmd::output::BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("hp", 100.0, "", FCVAR_NONE);
+        interpreter.commands_handler.register_cvar("god", false, "", FCVAR_NONE);
+
+        let check = r#"if $god { echo god } else if $hp > 50 { echo healthy } else if $hp > 0 { echo hurt } else { echo dead }"#;
+        interpreter.execute(check).unwrap();
+        interpreter.execute(&format!("hp 9.5; {}; hp 0; {}; god 1; {}", check, check, check)).unwrap();
+        assert_eq!(buffer.contents(), "healthy\nhurt\ndead\ngod\n");
+
+        // numbers compare as numbers, anything else as text
+        buffer.clear();
+        interpreter.execute(r#"alias team red; if $team == red { echo red }; if 10 > 9 { echo numbers }; if 10 < 9.0x { echo text }"#).unwrap();
+        interpreter.execute(r#"if "$team" != blue { if $team { alias team "" } }; if $team { echo never } else { echo "no team" }"#).unwrap();
+        assert_eq!(buffer.contents(), "red\nnumbers\ntext\nno team\n");
+
+        // errors in blocks point at the input, errors in aliases at the alias
+        interpreter.execute(r#"alias broken "if 1 { nope }""#).unwrap();
+        assert_eq!(interpreter.execute("if 1 { nope }").unwrap_err().span(), Some(Span::new(7, 11, 1, 8)));
+        assert_eq!(interpreter.execute("if 1 { broken }").unwrap_err().span(), Some(Span::new(7, 13, 1, 8)));
 
-        assert!(true);
+        // the rest of a block waits like the rest of the script
+        buffer.clear();
+        interpreter.execute("if 1 { echo a; wait; echo b }; echo c").unwrap();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "a\nb\nc\n");
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::command::CommandsHandler;
+    use haybcmd::lexer::{Lexer, LexerOptions};
+    use haybcmd::token::{Span, TokenType};
+    use haybcmd::ExecError;
+
+    fn values(input: &str, options: LexerOptions) -> Result<Vec<String>, ExecError> {
+        let commands_handler = CommandsHandler::new();
+        let mut lexer = Lexer::with_options(input.to_string(), options);
+
+        let mut values = vec!();
+        loop {
+            let token = lexer.next_token(&commands_handler)?;
+            if token.token_type() == &TokenType::EOF {
+                return Ok(values);
+            }
+            values.push(token.value().to_string());
+        }
+    }
+
+    #[test]
+    fn lexer_comments() {
+        let options = LexerOptions::default();
+
+        assert_eq!(values("// only a comment", options).unwrap(), Vec::<String>::new());
+        assert_eq!(values("echo a // b; echo c\necho d", options).unwrap(), vec!["echo", "a", "\n", "echo", "d"]);
+        assert_eq!(values("echo \"// not a comment\" # kept", options).unwrap(), vec!["echo", "// not a comment", "#", "kept"]);
+        assert_eq!(values("echo a /* b */ c", options).unwrap(), vec!["echo", "a", "/*", "b", "*/", "c"]);
+
+        let options = LexerOptions { hash_comments: true, block_comments: true };
+        assert_eq!(values("echo a # b\n/* multi\nline */ echo \"/* c */\"", options).unwrap(), vec!["echo", "a", "\n", "echo", "/* c */"]);
+        let err = values("echo /* open", options).unwrap_err();
+        assert_eq!(err.root(), &ExecError::UnterminatedComment);
+        assert_eq!(err.span(), Some(Span::new(5, 12, 1, 6)));
+    }
+
+    #[test]
+    fn lexer_spans() {
+        let commands_handler = CommandsHandler::new();
+        let mut lexer = Lexer::new("echo \"a b\";\n  x".to_string());
+
+        let mut spans = vec!();
+        loop {
+            let token = lexer.next_token(&commands_handler).unwrap();
+            spans.push(token.span());
+            if token.token_type() == &TokenType::EOF {
+                break;
+            }
+        }
+
+        assert_eq!(spans, vec![
+            Span::new(0, 4, 1, 1),
+            Span::new(5, 10, 1, 6),
+            Span::new(10, 11, 1, 11),
+            Span::new(11, 12, 1, 12),
+            Span::new(14, 15, 2, 3),
+            Span::new(15, 15, 2, 4),
+        ]);
+    }
+
+    #[test]
+    fn lexer_unicode() {
+        let commands_handler = CommandsHandler::new();
+        let mut lexer = Lexer::new("  héllo \"wörld ✓\";\t日本".to_string());
+
+        let mut tokens = vec!();
+        loop {
+            let token = lexer.next_token(&commands_handler).unwrap();
+            if token.token_type() == &TokenType::EOF {
+                break;
+            }
+            tokens.push((token.value().to_string(), token.span()));
+        }
+
+        assert_eq!(tokens, vec![
+            ("héllo".to_string(), Span::new(2, 8, 1, 3)),
+            ("wörld ✓".to_string(), Span::new(9, 21, 1, 9)),
+            (";".to_string(), Span::new(21, 22, 1, 18)),
+            ("日本".to_string(), Span::new(23, 29, 1, 20)),
+        ]);
+    }
+
+    #[test]
+    fn lexer_escapes() {
+        let options = LexerOptions::default();
+
+        assert_eq!(values(r#"echo "a\"b\\c\nd\te\;f\$g\u{e9}\u{1F600}\q""#, options).unwrap(), vec!["echo", "a\"b\\c\nd\te;f$g\u{e9}\u{1F600}\\q"]);
+        assert_eq!(values(r#"echo 'no \n "escapes" $here'; x"#, options).unwrap(), vec!["echo", "no \\n \"escapes\" $here", ";", "x"]);
+
+        let err = values("echo 'open", options).unwrap_err();
+        assert_eq!(err.root(), &ExecError::UnterminatedString);
+        assert_eq!(err.span(), Some(Span::new(5, 10, 1, 6)));
+
+        assert!(matches!(values(r#"echo "\u{110000}""#, options).unwrap_err().root(), ExecError::InvalidEscape(_)));
+        assert!(matches!(values(r#"echo "\u{41""#, options).unwrap_err().root(), ExecError::InvalidEscape(_)));
+        assert!(matches!(values(r#"echo "\u41""#, options).unwrap_err().root(), ExecError::InvalidEscape(_)));
+    }
+
+    #[test]
+    fn lexer_reads_quoted_values_back() {
+        for text in ["plain", "with \"quotes\"", "back\\slash\\", "new\nline\tand tab", "semi; colon", "cost $5 ${x}", "ünïcödé ✓", ""] {
+            let quoted = haybcmd::config::quote(text);
+            assert_eq!(values(&quoted, LexerOptions::default()).unwrap(), vec![text]);
+        }
+    }
+}
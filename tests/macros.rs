@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::output::{BufferSink, OutputSink};
+    use haybcmd::token::Span;
+    use haybcmd::{ExecError, Interpreter};
+
+    /// Gives the player some items
+    #[haybcmd::command(name = "give", usage = "gives an item to the player")]
+    fn give(interpreter: &mut Interpreter, item: String, count: u32, silent: Option<bool>) {
+        if !silent.unwrap_or(false) {
+            interpreter.output.println(&format!("{} x{}", item, count));
+        }
+    }
+
+    #[haybcmd::command(usage = "adds numbers")]
+    fn sum(interpreter: &mut Interpreter, scale: f32, numbers: Vec<i64>) -> Result<(), ExecError> {
+        if numbers.is_empty() {
+            return Err(ExecError::InvalidArgument {
+                command: "sum".to_string(),
+                argument: scale.to_string(),
+                reason: "nothing to add".to_string(),
+            });
+        }
+
+        let total: i64 = numbers.iter().sum();
+        interpreter.output.println(&(total as f32 * scale).to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn macros_generate_commands() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+
+        assert!(interpreter.register_definition(give));
+        assert!(interpreter.register_definition(sum));
+        assert!(!interpreter.register_definition(give));
+
+        assert_eq!(interpreter.commands_handler.get("give").unwrap().usage, "<item> <count:int> <silent:bool?> - gives an item to the player");
+        assert_eq!(interpreter.commands_handler.get("sum").unwrap().usage, "<scale:float> <numbers:int...?> - adds numbers");
+
+        interpreter.execute("give ammo 30; give health 5 on; sum 0.5 1 2 3").unwrap();
+        assert_eq!(buffer.contents(), "ammo x30\n3\n");
+
+        let err = interpreter.execute("give ammo -1").unwrap_err();
+        assert_eq!(err.root(), &ExecError::InvalidArgument {
+            command: "give".to_string(),
+            argument: "-1".to_string(),
+            reason: "count: out of range for u32".to_string(),
+        });
+        assert_eq!(err.span(), Some(Span::new(10, 12, 1, 11)));
+
+        assert!(matches!(interpreter.execute("give ammo many").unwrap_err().root(), ExecError::InvalidArgument { .. }));
+        assert!(matches!(interpreter.execute("sum 2").unwrap_err().root(), ExecError::InvalidArgument { .. }));
+    }
+
+    /// Commands still expand when the host has its own `Result` in scope
+    mod shadowed {
+        use haybcmd::output::OutputSink;
+        use haybcmd::Interpreter;
+
+        #[allow(dead_code)]
+        type Result<T> = std::result::Result<T, String>;
+
+        #[haybcmd::command(usage = "prints its arguments")]
+        pub fn show(interpreter: &mut Interpreter, first: Option<i64>, rest: Vec<String>) {
+            interpreter.output.println(&format!("{:?} {:?}", first, rest));
+        }
+    }
+
+    #[test]
+    fn macros_optional_then_variadic() {
+        let buffer = BufferSink::new();
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        assert!(interpreter.register_definition(shadowed::show));
+
+        interpreter.execute("show; show 5; show 5 a b").unwrap();
+        assert_eq!(buffer.contents(), "None []\nSome(5) []\nSome(5) [\"a\", \"b\"]\n");
+    }
+}
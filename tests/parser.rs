@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use haybcmd::ast::{Argument, ArgumentKind, CommandInvocation, Statement};
+    use haybcmd::output::BufferSink;
+    use haybcmd::token::{Span, StringPart};
+    use haybcmd::ExecError;
+
+    #[test]
+    fn parser_builds_script() {
+        let buffer = BufferSink::new();
+        let interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+
+        let script = interpreter.parse(";echo hi \"$name!\" $x;; nope").unwrap();
+        assert_eq!(buffer.contents(), "");
+        assert_eq!(script.statements, vec![
+            Statement::Command(CommandInvocation {
+                name: "echo".to_string(),
+                name_span: Span::new(1, 5, 1, 2),
+                args: vec![
+                    Argument { kind: ArgumentKind::Literal("hi".to_string()), span: Span::new(6, 8, 1, 7), written: "hi".to_string() },
+                    Argument {
+                        kind: ArgumentKind::Interpolated(vec![StringPart::Variable { name: "name".to_string(), written: "$name".to_string() }, StringPart::Text("!".to_string())]),
+                        span: Span::new(9, 17, 1, 10),
+                        written: "\"$name!\"".to_string(),
+                    },
+                    Argument { kind: ArgumentKind::Variable("x".to_string()), span: Span::new(18, 20, 1, 19), written: "$x".to_string() },
+                ],
+                span: Span::new(1, 20, 1, 2),
+            }),
+            Statement::Command(CommandInvocation {
+                name: "nope".to_string(),
+                name_span: Span::new(23, 27, 1, 24),
+                args: vec!(),
+                span: Span::new(23, 27, 1, 24),
+            }),
+        ]);
+
+        assert_eq!(interpreter.validate(&script).unwrap_err().to_string(), "1:24: unknown command \"nope\"");
+        assert_eq!(interpreter.parse("echo ok; echo \"open").unwrap_err().root(), &ExecError::UnterminatedString);
+    }
+
+    #[test]
+    fn parser_pretty_prints() {
+        let interpreter = haybcmd::Interpreter::with_output(BufferSink::new());
+
+        let script = interpreter.parse("echo   'two words'  ;alias \"\" \"a\\\"b\" ; echo \"${hp}% \\$\" $hp '$hp' \"//\"").unwrap();
+        let printed = script.to_string();
+        assert_eq!(printed, r#"echo "two words"; alias "" "a\"b"; echo "${hp}% \$" $hp "\$hp" "//""#);
+        assert_eq!(interpreter.parse(&printed).unwrap().to_string(), printed);
+    }
+
+    #[test]
+    fn parser_scripts_run_repeatedly() {
+        let buffer = BufferSink::new();
+        let mut interpreter = haybcmd::Interpreter::with_output(buffer.clone());
+        interpreter.commands_handler.register_cvar("hp", 100, "", haybcmd::cvar::FCVAR_NONE);
+
+        let script = Rc::new(interpreter.parse("echo \"hp $hp\"").unwrap());
+        interpreter.run(&script).unwrap();
+        interpreter.execute("hp 50").unwrap();
+        interpreter.run(&script).unwrap();
+
+        assert_eq!(buffer.contents(), "hp 100\nhp 50\n");
+    }
+
+    #[test]
+    fn parser_alias_recursion_limit() {
+        let mut interpreter = haybcmd::Interpreter::with_output(BufferSink::new());
+        interpreter.commands_handler.variables.insert("loop".to_string(), "loop".to_string());
+
+        let err = interpreter.execute("echo ok; loop").unwrap_err();
+        assert!(matches!(err.root(), ExecError::AliasRecursionLimit(_)));
+        assert_eq!(err.span(), Some(Span::new(9, 13, 1, 10)));
+    }
+
+    #[test]
+    fn parser_if_blocks() {
+        let interpreter = haybcmd::Interpreter::with_output(BufferSink::new());
+
+        let script = interpreter.parse("if $hp <= 0 { echo dead;echo '}' } else if $god { } else { echo { hi; echo \"\" }; echo after").unwrap();
+        let printed = script.to_string();
+        assert_eq!(printed, r#"if $hp <= 0 { echo dead; echo "}" } else { if $god { } else { echo "{" hi; echo "" } }; echo after"#);
+        assert_eq!(interpreter.parse(&printed).unwrap().to_string(), printed);
+        assert_eq!(script.statements[0].span(), Span::new(0, 79, 1, 1));
+
+        let errors = [
+            ("if { echo }", "1:4: syntax error: expected a value in the condition"),
+            ("if $a = 1 { echo }", "1:7: syntax error: expected a comparison or {"),
+            ("if $a == 1 echo", "1:12: syntax error: expected { after the condition"),
+            ("if 1 {echo x}", "1:6: syntax error: { has to be a word of its own"),
+            ("if $a { echo a", "1:15: syntax error: expected } before the end of the input"),
+            ("if $a { } echo", "1:11: syntax error: expected ; after }"),
+            ("if $a { } else echo", "1:16: syntax error: expected { or if after else"),
+        ];
+        for (input, error) in errors {
+            assert_eq!(interpreter.parse(input).unwrap_err().to_string(), error);
+        }
+
+        let script = interpreter.parse("if $a {\n    echo a\n}\n\nelse {\n}\nif $b { }\necho b").unwrap();
+        assert_eq!(script.to_string(), "if $a { echo a } else { }; if $b { }; echo b");
+
+        // braces only mean something as words of their own after an if
+        assert_eq!(interpreter.parse("echo } {x}").unwrap().to_string(), r#"echo "}" {x}"#);
+    }
+}
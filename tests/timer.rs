@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use haybcmd::output::BufferSink;
+    use haybcmd::timer::{ManualClock, Timers};
+    use haybcmd::Interpreter;
+
+    fn timed_interpreter() -> (Interpreter, BufferSink, ManualClock) {
+        let buffer = BufferSink::new();
+        let clock = ManualClock::new();
+
+        let mut interpreter = Interpreter::with_output(buffer.clone());
+        interpreter.timers.set_clock(clock.clone());
+
+        (interpreter, buffer, clock)
+    }
+
+    #[test]
+    fn timer_delay_and_repeat() {
+        let (mut interpreter, buffer, clock) = timed_interpreter();
+
+        interpreter.execute(r#"delay 1.5 echo later; repeat 1 3 "echo tick; wait; echo tock""#).unwrap();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "");
+
+        clock.advance(1.0);
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "tick\n");
+
+        clock.advance(0.5);
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "tick\ntock\nlater\n");
+
+        // behind by two intervals, the repeat catches up one run per tick
+        buffer.clear();
+        clock.advance(5.0);
+        interpreter.tick().unwrap();
+        interpreter.tick().unwrap();
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "tick\ntock\ntick\ntock\n");
+        assert!(interpreter.timers.is_empty());
+
+        // quoted text in the commands stays one argument when they run
+        buffer.clear();
+        interpreter.execute(r#"delay 1 echo "a;b"  "c  d"; repeat 1 1 echo 'x;y' z"#).unwrap();
+        clock.advance(1.0);
+        interpreter.tick().unwrap();
+        assert_eq!(buffer.contents(), "a;b c  d\nx;y z\n");
+    }
+
+    #[test]
+    fn timer_cancel() {
+        let clock = ManualClock::new();
+        let mut timers = Timers::with_clock(clock.clone());
+        let interpreter = Interpreter::with_output(BufferSink::new());
+
+        let once = timers.delay(1.0, interpreter.parse("echo once").unwrap());
+        let forever = timers.repeat(0.5, u32::MAX, interpreter.parse("echo again").unwrap());
+        assert_eq!(timers.len(), 2);
+
+        assert!(timers.cancel(once));
+        assert!(!timers.cancel(once));
+        assert!(timers.cancel(forever));
+        assert!(timers.is_empty());
+    }
+
+    #[test]
+    fn timer_errors() {
+        let (mut interpreter, buffer, clock) = timed_interpreter();
+
+        assert_eq!(interpreter.execute("delay -1 echo hi").unwrap_err().to_string(), "1:7: delay: invalid argument \"-1\": seconds: can not be negative");
+        assert_eq!(interpreter.execute("repeat 1 -2 echo hi").unwrap_err().to_string(), "1:10: repeat: invalid argument \"-2\": count: can not be negative");
+        assert_eq!(interpreter.execute("delay NaN echo hi").unwrap_err().to_string(), "1:7: delay: invalid argument \"NaN\": seconds: must be a finite number");
+        assert_eq!(interpreter.execute("delay inf echo hi").unwrap_err().to_string(), "1:7: delay: invalid argument \"inf\": seconds: must be a finite number");
+        assert_eq!(interpreter.execute("repeat -inf 2 echo hi").unwrap_err().to_string(), "1:8: repeat: invalid argument \"-inf\": interval: must be a finite number");
+        assert_eq!(interpreter.execute("repeat 1 99999999999 echo hi").unwrap_err().to_string(), "1:10: repeat: invalid argument \"99999999999\": count: can not be more than 4294967295");
+        let err = interpreter.execute(r#"delay 1 "echo \"unclosed""#).unwrap_err();
+        assert_eq!(err.to_string(), "1:1: unterminated string");
+        assert!(interpreter.timers.is_empty());
+
+        // a failing timer does not keep the others from running
+        interpreter.execute("delay 1 nope; delay 1 echo still").unwrap();
+        clock.advance(1.0);
+        assert!(interpreter.tick().is_err());
+        assert_eq!(buffer.contents(), "still\n");
+    }
+}
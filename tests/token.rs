@@ -4,7 +4,9 @@ mod tests {
     fn token() {
         let token = haybcmd::token::Token{
             token_type: haybcmd::token::TokenType::STRING,
-            value: String::from("Hello, World!")
+            value: String::from("Hello, World!"),
+            span: haybcmd::token::Span::default(),
+            parts: vec!()
         };
 
         assert_ne!(token.token_type.to_string(), haybcmd::token::TokenType::NOTHING.to_string());